@@ -1,45 +1,287 @@
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
-use anyhow::{bail, Result};
-use clap::{Parser, ValueEnum};
+use anyhow::{bail, Context, Result};
+use clap::{Parser, Subcommand, ValueEnum};
 use fff::{
+    consistency,
+    data::{DataSource, DATA_DIR_ENV, MANIFEST_FILE},
+    defense,
+    exclude::Exclusions,
+    filter::{query_context, query_sql},
+    form,
+    league::League,
+    lineup,
+    lookup::{Player, PlayerLookup},
     pbp::{PbpDf, PbpFilter},
     roster::{RosterDf, RosterFilter},
-    scoring::Scoring,
+    scoring::{FantasyStatsDf, Preset, Scoring},
+    td::{self, TdModel},
+    usage, vor,
+    xfp::{self, XfpModel},
     Position,
 };
 use itertools::Itertools;
 use log::LevelFilter;
+use output::{Output, OutputFormat};
 use parse_display::Display;
 use polars::prelude::*;
 use simplelog::{ColorChoice, ConfigBuilder, TermLogger, TerminalMode};
 
-fn crazy_shawn_scoring() -> Scoring {
-    let mut scoring = Scoring::ppr();
-    scoring.passing_300yd_bonus = 1.0;
-    scoring.passing_400yd_bonus = 1.0;
-    scoring.passing_td_50yd_bonus = 1.0;
-    scoring.rushing_100yd_bonus = 1.0;
-    scoring.rushing_200yd_bonus = 1.0;
-    scoring.rushing_td_50yd_bonus = 1.0;
-    scoring.receiving_100yd_bonus = 1.0;
-    scoring.receiving_200yd_bonus = 1.0;
-    scoring.receiving_td_50yd_bonus = 1.0;
-    scoring.fg_made_40yd_bonus = 1.0;
-    scoring.fg_made_50yd_bonus = 1.0;
-    scoring
-}
+mod output;
+mod repl;
+
+/// The `shawn` scoring preset, with bonuses for long touchdowns, big games, and long field goals
+const SHAWN_SCORING: &str = include_str!("../../leagues/shawn.toml");
 
 #[derive(Parser, Debug)]
 #[command(author, version, about)]
 struct Args {
-    #[arg(short, long, action = clap::ArgAction::Count)]
+    #[command(subcommand)]
+    command: Command,
+
+    #[arg(short, long, action = clap::ArgAction::Count, global = true)]
     verbose: u8,
 
-    /// Loads data for a given year
-    #[arg(short = 'y', long = "year", default_value_t = 2023)]
-    year: u16,
+    /// Directory containing the downloaded parquet data
+    /// [default: data_dir in ~/.config/fff/config.toml, or data]
+    #[arg(long = "data-dir", env = DATA_DIR_ENV, global = true)]
+    data_dir: Option<PathBuf>,
+
+    /// Loads data for a given year or range of years (e.g. 2023 or 2019-2023)
+    #[arg(
+        short = 'y',
+        long = "years",
+        alias = "year",
+        default_value = "2023",
+        global = true
+    )]
+    years: YearArg,
+
+    /// Format for printed results
+    #[arg(short = 'o', long = "output", value_enum, default_value_t = OutputFormat::Table, global = true)]
+    output: OutputFormat,
+
+    /// Write results to a file instead of stdout
+    #[arg(long = "out-file", global = true)]
+    out_file: Option<PathBuf>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Rank players (or games) by fantasy points
+    Score {
+        #[command(flatten)]
+        filters: FilterArgs,
+
+        #[command(flatten)]
+        scoring: ScoringArgs,
+
+        /// Choose how to aggregate scores
+        #[arg(long = "score-by", default_value_t = ScoreBy::Player)]
+        score_by: ScoreBy,
+    },
+
+    /// Rank players by recent form: rolling averages, medians, and recency-weighted averages
+    Form {
+        /// Number of most recent games in the rolling average and median
+        #[arg(long, default_value_t = 5)]
+        last: u32,
+
+        /// Only count games up to this week of the last loaded season
+        #[arg(long = "as-of")]
+        as_of: Option<u32>,
+
+        /// Weight of the latest game in the exponentially weighted average (0 to 1)
+        #[arg(long, default_value_t = form::DEFAULT_ALPHA)]
+        alpha: f64,
+
+        #[command(flatten)]
+        filters: FilterArgs,
+
+        #[command(flatten)]
+        scoring: ScoringArgs,
+    },
+
+    /// Profile how consistent each player's weekly scores are, with boom and bust rates
+    Consistency {
+        /// Leave out players with fewer games
+        #[arg(long = "min-games", default_value_t = 1)]
+        min_games: u32,
+
+        /// Weekly points that count as a boom, for every position [default: by position]
+        #[arg(long)]
+        boom: Option<f64>,
+
+        /// Weekly points that count as a bust, for every position [default: by position]
+        #[arg(long)]
+        bust: Option<f64>,
+
+        #[command(flatten)]
+        filters: FilterArgs,
+
+        #[command(flatten)]
+        scoring: ScoringArgs,
+    },
+
+    /// Rank players by opportunity: targets, carries, team shares, and red zone usage
+    Usage {
+        /// Show each player-game instead of totals per player
+        #[arg(long = "by-game")]
+        by_game: bool,
+
+        #[command(flatten)]
+        filters: FilterArgs,
+    },
+
+    /// Compare receiving and rushing points to expected points (xFP) from each play's situation
+    Xfp {
+        /// Show each player-game instead of totals per player
+        #[arg(long = "by-game")]
+        by_game: bool,
+
+        #[command(flatten)]
+        filters: FilterArgs,
+
+        #[command(flatten)]
+        scoring: ScoringArgs,
+    },
+
+    /// Compare touchdowns to expected touchdowns from each target's and carry's field position
+    Td {
+        /// Show each player-game instead of totals per player
+        #[arg(long = "by-game")]
+        by_game: bool,
+
+        #[command(flatten)]
+        filters: FilterArgs,
+
+        #[command(flatten)]
+        scoring: ScoringArgs,
+    },
+
+    /// Show the raw fantasy stats of each player-game, before scoring
+    Stats {
+        #[command(flatten)]
+        filters: FilterArgs,
+    },
+
+    /// List rostered players, e.g. to look up gsis IDs
+    Players {
+        #[command(flatten)]
+        filters: FilterArgs,
+    },
+
+    /// Rank defenses by the fantasy points they allow to each position
+    Defense {
+        /// Only show this defense
+        #[arg(short = 't', long = "team")]
+        team: Option<String>,
 
+        /// Only show this position (qb, rb, wr, te, or k)
+        #[arg(short = 'p', long = "pos", value_enum)]
+        position: Option<Position>,
+
+        /// Only count games in this week number or range (e.g. 3 or 3-5)
+        #[arg(short = 'w', long = "week", alias = "weeks")]
+        weeks: Option<WeekArg>,
+
+        #[command(flatten)]
+        scoring: ScoringArgs,
+    },
+
+    /// Rank players by value over replacement (VOR) for a league's starting lineups
+    Vor {
+        #[command(flatten)]
+        league: LeagueArgs,
+
+        #[command(flatten)]
+        exclude: ExcludeArgs,
+    },
+
+    /// Pick the highest scoring lineup from a fantasy roster for one week
+    Lineup {
+        /// Players on the roster, by name or gsis ID, and team defenses by abbreviation (e.g. KC)
+        #[arg(long, required = true, value_delimiter = ',')]
+        players: Vec<String>,
+
+        /// Week of the last loaded season to set the lineup for
+        #[arg(short = 'w', long)]
+        week: u16,
+
+        /// Choose by projected points instead of actual scores, from a CSV or parquet file with
+        /// player_id and projected_points columns (and optionally week)
+        #[arg(long)]
+        projections: Option<PathBuf>,
+
+        /// Players that were actually started, to see how many points were left on the bench
+        #[arg(long, value_delimiter = ',')]
+        started: Vec<String>,
+
+        #[command(flatten)]
+        league: LeagueArgs,
+    },
+
+    /// Score every player in a single game
+    Game {
+        /// Game ID as it appears in the play-by-play data (e.g. 2023_01_DET_KC)
+        game_id: String,
+
+        #[command(flatten)]
+        scoring: ScoringArgs,
+
+        #[command(flatten)]
+        exclude: ExcludeArgs,
+    },
+
+    /// Itemize a player's fantasy points and list the plays behind them
+    Explain {
+        /// Player name (e.g. "mahomes", "Pat Mahomes", "P.Mahomes") or gsis ID
+        #[arg(long)]
+        player: String,
+
+        /// Filtering week number or range (e.g. 3 or 3-5)
+        #[arg(short = 'w', long = "week", alias = "weeks")]
+        weeks: Option<WeekArg>,
+
+        #[command(flatten)]
+        scoring: ScoringArgs,
+
+        #[command(flatten)]
+        exclude: ExcludeArgs,
+    },
+
+    /// Run a SQL query against the `plays`, `rosters`, and scored `fantasy` tables
+    Query {
+        /// e.g. "select player_name, sum(fantasy_points) from fantasy group by player_name"
+        sql: String,
+
+        #[command(flatten)]
+        scoring: ScoringArgs,
+
+        #[command(flatten)]
+        exclude: ExcludeArgs,
+    },
+
+    /// Load the data once and run SQL or built-in commands interactively
+    Repl {
+        #[command(flatten)]
+        scoring: ScoringArgs,
+
+        #[command(flatten)]
+        exclude: ExcludeArgs,
+    },
+
+    /// List the datasets and seasons present in the data directory
+    Data {
+        /// Save the listing as manifest.json in the data directory
+        #[arg(long)]
+        write: bool,
+    },
+}
+
+#[derive(clap::Args, Clone, Debug, Default)]
+struct FilterArgs {
     /// Filter by team
     #[arg(short = 't', long = "team")]
     team: Option<String>,
@@ -52,16 +294,161 @@ struct Args {
     #[arg(short = 'w', long = "week", alias = "weeks")]
     weeks: Option<WeekArg>,
 
+    #[command(flatten)]
+    exclude: ExcludeArgs,
+
+    /// Only count games against this opponent (e.g. KC)
+    #[arg(long)]
+    vs: Option<String>,
+
+    /// Filter to one player by name (e.g. "mahomes", "Pat Mahomes", "P.Mahomes") or gsis ID
+    #[arg(long)]
+    player: Option<String>,
+}
+
+impl FilterArgs {
+    fn pbp_filter(&self) -> PbpFilter {
+        let mut pbp_filter = PbpFilter::new();
+        if let Some(team) = &self.team {
+            // The team's defense and returners are credited on plays where it doesn't have the ball
+            pbp_filter = pbp_filter.involving_team(team);
+        }
+        if let Some(opponent) = &self.vs {
+            // Only games the opponent played in can count against them
+            pbp_filter = pbp_filter.involving_team(opponent);
+        }
+        match self.weeks {
+            Some(WeekArg::Week(week)) => pbp_filter.week(week),
+            Some(WeekArg::WeekRange(week, through)) => pbp_filter.week_range(week, through),
+            None => pbp_filter,
+        }
+    }
+
+    fn roster_filter(&self) -> RosterFilter {
+        let mut roster_filter = RosterFilter::new();
+        if let Some(team) = &self.team {
+            roster_filter = roster_filter.team(team);
+        }
+        if let Some(pos) = self.position {
+            roster_filter = roster_filter.position(pos);
+        }
+        match self.weeks {
+            Some(WeekArg::Week(week)) => roster_filter.week(week),
+            Some(WeekArg::WeekRange(week, through)) => roster_filter.week_range(week, through),
+            None => roster_filter,
+        }
+    }
+
+    /// Resolves --player (if any) to a gsis ID
+    fn player_id(&self, source: &DataSource, years: &[u16]) -> Result<Option<String>> {
+        let Some(query) = &self.player else {
+            return Ok(None);
+        };
+        let lookup = player_lookup(source, years)?;
+        let player = lookup.resolve(query)?;
+        log::info!("Filtering to {}", player);
+        Ok(Some(player.gsis_id.clone()))
+    }
+
+    fn excluded_ids(&self, source: &DataSource, years: &[u16]) -> Result<Vec<String>> {
+        self.exclude.excluded_ids(source, years)
+    }
+}
+
+#[derive(clap::Args, Clone, Debug, Default)]
+struct ExcludeArgs {
+    /// Leave out players listed in a file, one name, gsis ID, or team defense (e.g. KC) per line
+    /// (# for comments)
     #[arg(short = 'x', long = "exclude")]
     exclude: Option<PathBuf>,
+}
 
-    /// Calculate fantasy score
-    #[arg(long)]
+impl ExcludeArgs {
+    /// Resolves the exclusion file (if any) to player IDs
+    fn excluded_ids(&self, source: &DataSource, years: &[u16]) -> Result<Vec<String>> {
+        if self.exclude.is_none() {
+            return Ok(Vec::new());
+        }
+        self.resolve(&player_lookup(source, years)?)
+    }
+
+    /// Resolves the exclusion file (if any) with an already loaded player lookup
+    fn resolve(&self, lookup: &PlayerLookup) -> Result<Vec<String>> {
+        let Some(path) = &self.exclude else {
+            return Ok(Vec::new());
+        };
+        let exclusions = Exclusions::from_file(path)
+            .with_context(|| format!("Failed to read exclusions from {}", path.display()))?;
+        let player_ids = exclusions
+            .player_ids(lookup)
+            .with_context(|| format!("Failed to resolve exclusions in {}", path.display()))?;
+        log::info!(
+            "Excluding {} players listed in {}",
+            player_ids.len(),
+            path.display()
+        );
+        Ok(player_ids)
+    }
+}
+
+#[derive(clap::Args, Debug)]
+struct ScoringArgs {
+    /// Calculate fantasy score using a preset (ppr, half-ppr, no-ppr, shawn) [default: ppr]
+    #[arg(long, conflicts_with = "score_file")]
     score: Option<String>,
 
-    /// Choose how to aggregate scores
-    #[arg(long = "score-by", default_value_t = ScoreBy::Player)]
-    score_by: ScoreBy,
+    /// Calculate fantasy score using a TOML or JSON scoring config
+    #[arg(long = "score-file")]
+    score_file: Option<PathBuf>,
+}
+
+impl ScoringArgs {
+    fn scoring(&self) -> Result<Scoring> {
+        Ok(self.custom()?.unwrap_or_else(Scoring::ppr))
+    }
+
+    /// The scoring given by --score or --score-file, if either was
+    fn custom(&self) -> Result<Option<Scoring>> {
+        match (&self.score, &self.score_file) {
+            (Some(score), _) => preset_scoring(score).map(Some),
+            (None, Some(path)) => Scoring::from_file(path)
+                .map(Some)
+                .with_context(|| format!("Failed to load scoring from {}", path.display())),
+            (None, None) => Ok(None),
+        }
+    }
+}
+
+#[derive(clap::Args, Debug)]
+struct LeagueArgs {
+    /// League settings (teams, lineup slots, and scoring) from a TOML or JSON file
+    /// [default: 12 teams, 1 QB, 2 RB, 2 WR, 1 TE, 1 FLEX, 1 K, 1 DST, PPR]
+    #[arg(long)]
+    league: Option<PathBuf>,
+
+    /// Number of teams in the league, overriding the league file
+    #[arg(long)]
+    teams: Option<u32>,
+
+    #[command(flatten)]
+    scoring: ScoringArgs,
+}
+
+impl LeagueArgs {
+    fn league(&self) -> Result<League> {
+        let mut league = match &self.league {
+            Some(path) => League::from_file(path)
+                .with_context(|| format!("Failed to load league from {}", path.display()))?,
+            None => League::default(),
+        };
+        if let Some(teams) = self.teams {
+            league.teams = teams;
+        }
+        if let Some(scoring) = self.scoring.custom()? {
+            league.scoring = scoring;
+        }
+        Ok(league)
+    }
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Display)]
@@ -69,6 +456,8 @@ struct Args {
 pub enum ScoreBy {
     PlayerGame,
     Player,
+    PlayerSeason,
+    PlayerOpponent,
     Game,
     // FantasyTeam,
 }
@@ -79,6 +468,32 @@ enum WeekArg {
     WeekRange(u16, u16),
 }
 
+#[derive(Copy, Clone, Debug)]
+struct YearArg {
+    first: u16,
+    last: u16,
+}
+
+impl YearArg {
+    fn years(&self) -> Vec<u16> {
+        (self.first..=self.last).collect()
+    }
+}
+
+impl std::str::FromStr for YearArg {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.parse()? {
+            WeekArg::Week(year) => Ok(YearArg {
+                first: year,
+                last: year,
+            }),
+            WeekArg::WeekRange(first, last) => Ok(YearArg { first, last }),
+        }
+    }
+}
+
 impl std::str::FromStr for WeekArg {
     type Err = std::num::ParseIntError;
 
@@ -116,97 +531,892 @@ fn main() -> Result<()> {
 
     log::trace!("Args {:#?}", args);
 
-    let mut pbp_filter = PbpFilter::new();
-    let mut roster_filter = RosterFilter::new();
+    let source = DataSource::locate(args.data_dir.clone())?;
+    let output = Output {
+        format: args.output,
+        file: args.out_file.clone(),
+        ..Default::default()
+    };
+    let years = args.years.years();
+
+    match &args.command {
+        Command::Score {
+            filters,
+            scoring,
+            score_by,
+        } => score(
+            &source,
+            &output,
+            &years,
+            filters,
+            scoring.scoring()?,
+            *score_by,
+        ),
+        Command::Form {
+            last,
+            as_of,
+            alpha,
+            filters,
+            scoring,
+        } => player_form(
+            &source,
+            &output,
+            &years,
+            filters,
+            scoring.scoring()?,
+            *last,
+            *as_of,
+            *alpha,
+        ),
+        Command::Consistency {
+            min_games,
+            boom,
+            bust,
+            filters,
+            scoring,
+        } => consistency(
+            &source,
+            &output,
+            &years,
+            filters,
+            scoring.scoring()?,
+            *min_games,
+            *boom,
+            *bust,
+        ),
+        Command::Usage { by_game, filters } => {
+            player_usage(&source, &output, &years, filters, *by_game)
+        }
+        Command::Xfp {
+            by_game,
+            filters,
+            scoring,
+        } => expected_points(
+            &source,
+            &output,
+            &years,
+            filters,
+            scoring.scoring()?,
+            *by_game,
+        ),
+        Command::Td {
+            by_game,
+            filters,
+            scoring,
+        } => expected_touchdowns(
+            &source,
+            &output,
+            &years,
+            filters,
+            scoring.scoring()?,
+            *by_game,
+        ),
+        Command::Stats { filters } => stats(&source, &output, &years, filters),
+        Command::Players { filters } => players(&source, &output, &years, filters),
+        Command::Defense {
+            team,
+            position,
+            weeks,
+            scoring,
+        } => defense(
+            &source,
+            &output,
+            &years,
+            team.as_deref(),
+            *position,
+            *weeks,
+            scoring.scoring()?,
+        ),
+        Command::Vor { league, exclude } => vor(
+            &source,
+            &output,
+            &years,
+            &league.league()?,
+            &exclude.excluded_ids(&source, &years)?,
+        ),
+        Command::Lineup {
+            players,
+            week,
+            projections,
+            started,
+            league,
+        } => lineup(
+            &source,
+            &output,
+            &years,
+            players,
+            *week,
+            projections.as_deref(),
+            started,
+            &league.league()?,
+        ),
+        Command::Game {
+            game_id,
+            scoring,
+            exclude,
+        } => game(
+            &source,
+            &output,
+            &years,
+            game_id,
+            scoring.scoring()?,
+            exclude,
+        ),
+        Command::Explain {
+            player,
+            weeks,
+            scoring,
+            exclude,
+        } => explain(
+            &source,
+            &output,
+            &years,
+            player,
+            *weeks,
+            scoring.scoring()?,
+            exclude,
+        ),
+        Command::Query {
+            sql,
+            scoring,
+            exclude,
+        } => query(
+            &source,
+            &output,
+            &years,
+            sql,
+            scoring.scoring()?,
+            &exclude.excluded_ids(&source, &years)?,
+        ),
+        Command::Repl { scoring, exclude } => {
+            repl::run(&source, &years, output, scoring.scoring()?, exclude)
+        }
+        Command::Data { write } => data(&source, &output, *write),
+    }
+}
+
+/// Loads fantasy stats for the filtered plays, narrowed to the filtered teams, positions, and players
+fn fantasy_stats(
+    source: &DataSource,
+    years: &[u16],
+    filters: &FilterArgs,
+) -> Result<FantasyStatsDf> {
+    let player_id = filters.player_id(source, years)?;
 
-    if let Some(team) = args.team {
-        pbp_filter = pbp_filter.team(&team);
-        roster_filter = roster_filter.team(&team);
+    // Nothing is read from disk until the final output is collected
+    let mut pbp_df = PbpDf::load_seasons(source, years)?.filter(filters.pbp_filter().build())?;
+    if let Some(player_id) = &player_id {
+        // Whole games rather than only the player's plays, so team totals (e.g. target share)
+        // aren't just the player's own
+        pbp_df = pbp_df.games_with(PbpFilter::new().player_id(player_id).build())?;
     }
+    narrow_player_games(source, years, filters, player_id, pbp_df.fantasy_stats()?)
+}
 
-    if let Some(pos) = args.position {
-        roster_filter = roster_filter.position(pos);
-        // pbp doesn't have a way to filter on position, so we have to do it after joining with roster df
+/// Narrows player-games computed from the filtered plays to the filtered player, then applies the
+/// rest of the filters with `narrow_stats`
+fn narrow_player_games(
+    source: &DataSource,
+    years: &[u16],
+    filters: &FilterArgs,
+    player_id: Option<String>,
+    mut player_games: FantasyStatsDf,
+) -> Result<FantasyStatsDf> {
+    if let Some(player_id) = &player_id {
+        // The player's plays also credit teammates and opponents
+        player_games = player_games.filter(col("player_id").eq(lit(player_id.as_str())))?;
     }
 
-    match args.weeks {
+    let excluded_ids = filters.excluded_ids(source, years)?;
+    narrow_stats(player_games, filters, &excluded_ids, || {
+        Ok(RosterDf::load_seasons(source, years)?)
+    })
+}
+
+/// Matches player names against the rosters and play-by-play data for the given seasons
+fn player_lookup(source: &DataSource, years: &[u16]) -> Result<PlayerLookup> {
+    let roster_df = RosterDf::load_seasons(source, years)?;
+    let pbp_df = PbpDf::load_seasons(source, years)?;
+    Ok(PlayerLookup::load(roster_df, pbp_df)?)
+}
+
+/// Narrows fantasy stats to the filtered weeks, team, and position, leaving out excluded players.
+/// The roster is only needed (and loaded) to filter by position.
+fn narrow_stats(
+    mut fantasy_stats: FantasyStatsDf,
+    filters: &FilterArgs,
+    excluded_ids: &[String],
+    roster_df: impl FnOnce() -> Result<RosterDf>,
+) -> Result<FantasyStatsDf> {
+    match filters.weeks {
         Some(WeekArg::Week(week)) => {
-            pbp_filter = pbp_filter.week(week);
-            roster_filter = roster_filter.week(week);
+            fantasy_stats = fantasy_stats.filter(col("week").eq(lit(week as u32)))?;
         }
         Some(WeekArg::WeekRange(week, through)) => {
-            pbp_filter = pbp_filter.week_range(week, through);
-            roster_filter = roster_filter.week_range(week, through);
+            let expr = col("week").is_between(week as u32, through as u32, ClosedInterval::Both);
+            fantasy_stats = fantasy_stats.filter(expr)?;
         }
         None => {}
     }
 
-    if let Some(_exclude) = args.exclude {
-        todo!("Implement --exclude");
+    if let Some(team) = &filters.team {
+        // The team's plays also include their opponent's stats
+        fantasy_stats = fantasy_stats.filter(col("team").eq(lit(team.as_str())))?;
+    }
+
+    if let Some(opponent) = &filters.vs {
+        fantasy_stats = fantasy_stats.filter(col("opponent").eq(lit(opponent.as_str())))?;
     }
 
-    // Finalize the filters
-    let pbp_filter = pbp_filter.build();
-    let roster_filter = roster_filter.build();
+    fantasy_stats = fantasy_stats.exclude(excluded_ids)?;
 
-    let pbp_df = PbpDf::load(args.year)?;
-    log::info!("Loaded {} plays", pbp_df.height());
+    match filters.position {
+        // Team defenses aren't on the roster, so they are selected directly from the stats
+        Some(Position::Dst) => fantasy_stats = fantasy_stats.defenses()?,
+        Some(position) => {
+            // pbp doesn't have a way to filter on position, so we have to do it after joining with roster df
+            let position_df = roster_df()?
+                .filter(filters.roster_filter().build())?
+                .unique_players()?;
 
-    let game_df = pbp_df.filter(pbp_filter)?;
-    log::info!("Filtered to {} plays", game_df.height());
-    let mut fantasy_stats = game_df.fantasy_stats()?;
+            if log::log_enabled!(log::Level::Debug) {
+                log::debug!("Filtered players for position: {}", position);
+                let print_cols = ["week", "team", "position", "gsis_id", "full_name", "status"];
+                debug_df(position_df.clone().collect()?, &print_cols)?;
+            }
 
-    if let Some(position) = args.position {
-        let roster_df = RosterDf::load(args.year)?;
-        log::info!("Loaded {} roster entries", roster_df.height());
-        let position_df = roster_df.filter(roster_filter)?.unique_players()?;
+            fantasy_stats = fantasy_stats.merge_roster(position_df)?;
+        }
+        None => {}
+    }
+    Ok(fantasy_stats)
+}
 
-        log::info!("Filtered players for position: {}", position);
-        let print_cols = ["week", "team", "position", "gsis_id", "full_name", "status"];
-        debug_df(&position_df, &print_cols)?;
+fn score(
+    source: &DataSource,
+    output: &Output,
+    years: &[u16],
+    filters: &FilterArgs,
+    scoring: Scoring,
+    score_by: ScoreBy,
+) -> Result<()> {
+    let (filters, opponent) = split_opponent(filters, score_by);
+    let fantasy_stats = fantasy_stats(source, years, &filters)?;
+    print_scores(
+        output,
+        fantasy_stats,
+        scoring,
+        score_by,
+        opponent.as_deref(),
+    )
+}
+
+/// Player-opponent scores compare each opponent to the player's average over every game
+/// (`vs_avg`), so `--vs` picks the output rows instead of narrowing the games scored
+fn split_opponent(filters: &FilterArgs, score_by: ScoreBy) -> (FilterArgs, Option<String>) {
+    let mut filters = filters.clone();
+    let opponent = match score_by {
+        ScoreBy::PlayerOpponent => filters.vs.take(),
+        _ => None,
+    };
+    (filters, opponent)
+}
+
+fn print_scores(
+    output: &Output,
+    fantasy_stats: FantasyStatsDf,
+    scoring: Scoring,
+    score_by: ScoreBy,
+    opponent: Option<&str>,
+) -> Result<()> {
+    let mut scores = match score_by {
+        ScoreBy::Player => fantasy_stats.score_by_player(scoring)?,
+        ScoreBy::PlayerGame => fantasy_stats.score(scoring)?,
+        ScoreBy::PlayerSeason => fantasy_stats.score_by_player_season(scoring)?,
+        ScoreBy::PlayerOpponent => fantasy_stats.score_by_player_opponent(scoring)?,
+        ScoreBy::Game => fantasy_stats.score_by_game(scoring)?,
+    };
+    if let Some(opponent) = opponent {
+        scores = scores
+            .lazy()
+            .filter(col("opponent").eq(lit(opponent)))
+            .collect()?;
+    }
+    let print_cols = match score_by {
+        ScoreBy::Player => all(),
+        ScoreBy::PlayerSeason => all(),
+        ScoreBy::PlayerOpponent => all(),
+        ScoreBy::Game => all(),
+        ScoreBy::PlayerGame => cols([
+            "game_id",
+            "season",
+            "week",
+            "team",
+            "opponent",
+            "player_id",
+            "fantasy_points",
+        ]),
+    };
+    log::info!("Fantasy points for query");
+    print_df(output, scores, print_cols)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn player_form(
+    source: &DataSource,
+    output: &Output,
+    years: &[u16],
+    filters: &FilterArgs,
+    scoring: Scoring,
+    last: u32,
+    as_of: Option<u32>,
+    alpha: f64,
+) -> Result<()> {
+    if last == 0 {
+        bail!("--last must be at least 1");
+    }
+    if !(0.0..1.0).contains(&alpha) {
+        bail!("--alpha must be at least 0 and less than 1");
+    }
+    let scores = fantasy_stats(source, years, filters)?.score(scoring)?;
+    let form_df = form::form(scores, last, as_of, alpha)?;
+    print_df(output, form_df, all())
+}
 
-        // Merge roster_df with fantasy_stats
-        fantasy_stats = fantasy_stats.merge_roster(position_df)?;
-        log::info!("Stats for position: {}", position);
-        let print_cols = cols(["game_id", "player_id", "player_name", "position"]);
-        if args.score.is_none() {
-            print_df(&fantasy_stats, print_cols)?;
+#[allow(clippy::too_many_arguments)]
+fn consistency(
+    source: &DataSource,
+    output: &Output,
+    years: &[u16],
+    filters: &FilterArgs,
+    scoring: Scoring,
+    min_games: u32,
+    boom: Option<f64>,
+    bust: Option<f64>,
+) -> Result<()> {
+    let roster_df = RosterDf::load_seasons(source, years)?;
+    // Thresholds follow the scoring across every player, not just the filtered ones
+    let all_stats = PbpDf::load_seasons(source, years)?.fantasy_stats()?;
+    let thresholds = consistency::scaled_thresholds(all_stats, roster_df.clone(), scoring)?
+        .into_iter()
+        .map(|(position, scaled)| {
+            let thresholds = consistency::Thresholds {
+                boom: boom.unwrap_or(scaled.boom),
+                bust: bust.unwrap_or(scaled.bust),
+            };
+            (position, thresholds)
+        })
+        .collect::<Vec<_>>();
+    let scores = fantasy_stats(source, years, filters)?.score(scoring)?;
+    let consistency_df = consistency::consistency(scores, roster_df, &thresholds)?
+        .lazy()
+        .filter(col("games").gt_eq(lit(min_games)))
+        .collect()?;
+    print_df(output, consistency_df, all())
+}
+
+fn player_usage(
+    source: &DataSource,
+    output: &Output,
+    years: &[u16],
+    filters: &FilterArgs,
+    by_game: bool,
+) -> Result<()> {
+    let fantasy_stats = fantasy_stats(source, years, filters)?;
+    if !by_game {
+        return print_df(output, usage::usage_by_player(fantasy_stats)?, all());
+    }
+
+    let usage_df = fantasy_stats
+        .lazy()
+        .filter(col("targets").gt(lit(0)).or(col("carries").gt(lit(0))))
+        .sort(
+            ["wopr"],
+            SortMultipleOptions::default().with_order_descending(true),
+        )
+        .collect()?;
+    let print_cols = ["game_id", "week", "team", "player_id", "player_name"]
+        .into_iter()
+        .chain(usage::USAGE_STATS)
+        .collect::<Vec<_>>();
+    print_df(output, usage_df, cols(print_cols))
+}
+
+fn expected_points(
+    source: &DataSource,
+    output: &Output,
+    years: &[u16],
+    filters: &FilterArgs,
+    scoring: Scoring,
+    by_game: bool,
+) -> Result<()> {
+    let pbp_df = PbpDf::load_seasons(source, years)?;
+    // Expectations come from every play, not just the filtered ones
+    let model = XfpModel::fit(pbp_df.clone())?;
+
+    let player_id = filters.player_id(source, years)?;
+    let mut pbp_filter = filters.pbp_filter();
+    if let Some(player_id) = &player_id {
+        pbp_filter = pbp_filter.player_id(player_id);
+    }
+    let player_games = model.expected_points(pbp_df.filter(pbp_filter.build())?, scoring)?;
+
+    // Narrowed like fantasy stats, keeping only the xFP columns
+    let columns = player_games.get_column_names_owned();
+    let player_games = FantasyStatsDf::new(player_games.lazy());
+    let player_games = narrow_player_games(source, years, filters, player_id, player_games)?
+        .lazy()
+        .select(columns.iter().map(|name| col(name)).collect::<Vec<_>>())
+        .sort(
+            ["points_over_expected"],
+            SortMultipleOptions::default().with_order_descending(true),
+        )
+        .collect()?;
+    let xfp_df = if by_game {
+        player_games
+    } else {
+        xfp::xfp_by_player(player_games)?
+    };
+    print_df(output, xfp_df, all())
+}
+
+fn expected_touchdowns(
+    source: &DataSource,
+    output: &Output,
+    years: &[u16],
+    filters: &FilterArgs,
+    scoring: Scoring,
+    by_game: bool,
+) -> Result<()> {
+    // Touchdown rates come from every play, not just the filtered ones
+    let pbp_df = PbpDf::load_seasons(source, years)?;
+    let model = TdModel::fit(pbp_df.clone())?;
+    let expected = model.expected_touchdowns(pbp_df)?;
+
+    let scores = fantasy_stats(source, years, filters)?.score(scoring)?;
+    let player_games = td::td_regression(scores, expected, scoring)?;
+    let td_df = if by_game {
+        player_games
+    } else {
+        td::td_regression_by_player(player_games)?
+    };
+    print_df(output, td_df, all())
+}
+
+fn stats(source: &DataSource, output: &Output, years: &[u16], filters: &FilterArgs) -> Result<()> {
+    let fantasy_stats = fantasy_stats(source, years, filters)?.collect()?;
+    log::info!("Stats for {} player-games", fantasy_stats.height());
+    print_df(output, fantasy_stats, all())
+}
+
+fn players(
+    source: &DataSource,
+    output: &Output,
+    years: &[u16],
+    filters: &FilterArgs,
+) -> Result<()> {
+    if filters.position == Some(Position::Dst) {
+        bail!("Team defenses aren't on the roster; try `fff stats --pos dst`");
+    }
+
+    let mut roster_filter = filters.roster_filter();
+    if let Some(player_id) = filters.player_id(source, years)? {
+        roster_filter = roster_filter.player_id(&player_id);
+    }
+
+    let players_df = RosterDf::load_seasons(source, years)?
+        .filter(roster_filter.build())?
+        .unique_players()?
+        .exclude(&filters.excluded_ids(source, years)?)?
+        .collect()?;
+    let print_cols = cols([
+        "season",
+        "team",
+        "position",
+        "gsis_id",
+        "full_name",
+        "status",
+    ]);
+    print_df(output, players_df, print_cols)
+}
+
+fn defense(
+    source: &DataSource,
+    output: &Output,
+    years: &[u16],
+    team: Option<&str>,
+    position: Option<Position>,
+    weeks: Option<WeekArg>,
+    scoring: Scoring,
+) -> Result<()> {
+    let position = match position {
+        Some(Position::Qb | Position::Rb | Position::Wr | Position::Te | Position::K) | None => {
+            position
         }
+        Some(position) => bail!("Points allowed aren't ranked for {}", position),
+    };
+    let weeks = match weeks {
+        Some(WeekArg::Week(week)) => Some((week, week)),
+        Some(WeekArg::WeekRange(week, through)) => Some((week, through)),
+        None => None,
+    };
+
+    // Every game is loaded, since season averages of the opposing players don't depend on the window
+    let fantasy_stats = PbpDf::load_seasons(source, years)?.fantasy_stats()?;
+    let roster_df = RosterDf::load_seasons(source, years)?;
+    let mut lf = defense::points_allowed(fantasy_stats, roster_df, scoring, weeks)?.lazy();
+    if let Some(team) = team {
+        lf = lf.filter(col("defteam").eq(lit(team.to_uppercase())));
+    }
+    if let Some(position) = position {
+        lf = lf.filter(col("position").eq(lit(position.to_string().to_uppercase())));
     }
+    print_df(output, lf.collect()?, all())
+}
 
-    if let Some(score) = args.score {
-        let scoring = match &*score {
-            "ppr" => Scoring::ppr(),
-            "half-ppr" => Scoring::half_ppr(),
-            "no-ppr" => Scoring::no_ppr(),
-            "shawn" => crazy_shawn_scoring(),
-            _ => bail!("Unsupported scoring. Use: ppr, half-ppr, no-ppr, or shawn"),
-        };
-        let scores = match args.score_by {
-            ScoreBy::Player => fantasy_stats.score_by_player(scoring)?,
-            ScoreBy::PlayerGame => fantasy_stats.score(scoring)?,
-            ScoreBy::Game => fantasy_stats.score_by_game(scoring)?,
-        };
-        let print_cols = match args.score_by {
-            ScoreBy::Player => all(),
-            ScoreBy::Game => all(),
-            ScoreBy::PlayerGame => cols(["game_id", "week", "team", "player_id", "fantasy_points"]),
-        };
-        log::info!("Fantasy points for query");
-        print_df(&scores, print_cols)?;
+fn vor(
+    source: &DataSource,
+    output: &Output,
+    years: &[u16],
+    league: &League,
+    excluded_ids: &[String],
+) -> Result<()> {
+    // Replacement levels are for one season, so players are valued on their average season
+    let scores = PbpDf::load_seasons(source, years)?
+        .fantasy_stats()?
+        .score_by_player_season(league.scoring)?
+        .lazy()
+        .group_by([col("player_id")])
+        .agg([
+            cols(["player_name", "team"]).last(),
+            col("fantasy_points").mean(),
+        ])
+        .collect()?;
+    let roster_df = RosterDf::load_seasons(source, years)?;
+    // Replacement levels still count excluded players, e.g. those already drafted
+    let excluded = Series::new("excluded", excluded_ids);
+    let vor_df = vor::vor(scores, roster_df, league)?
+        .lazy()
+        .filter(col("player_id").is_in(lit(excluded)).not())
+        .collect()?;
+    print_df(output, vor_df, all())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn lineup(
+    source: &DataSource,
+    output: &Output,
+    years: &[u16],
+    players: &[String],
+    week: u16,
+    projections: Option<&Path>,
+    started: &[String],
+    league: &League,
+) -> Result<()> {
+    let season = *years.last().context("No seasons to load")?;
+    let lookup = player_lookup(source, &[season])?;
+    let resolve = |entries: &[String]| -> Result<Vec<String>> {
+        entries
+            .iter()
+            .map(|entry| {
+                let entry = entry.trim();
+                // Team defenses go by their abbreviation
+                if lookup.is_team(entry) {
+                    return Ok(entry.to_uppercase());
+                }
+                Ok(lookup.resolve(entry)?.gsis_id.clone())
+            })
+            .collect()
+    };
+    let player_ids = resolve(players)?;
+    let roster_spots = league.slots.roster_spots() as usize;
+    if player_ids.len() > roster_spots {
+        bail!(
+            "{} players don't fit the league's {} roster spots (starters, bench, and IR)",
+            player_ids.len(),
+            roster_spots
+        );
+    }
+    let started_ids = resolve(started)?;
+    if let Some(missing) = started_ids.iter().find(|id| !player_ids.contains(id)) {
+        bail!("Started player {} isn't one of --players", missing);
+    }
+
+    let fantasy_stats = PbpDf::load_seasons(source, &[season])?
+        .filter(PbpFilter::new().week(week).build())?
+        .fantasy_stats()?;
+    let roster_df = RosterDf::load_seasons(source, &[season])?;
+    let actual = lineup::player_points(fantasy_stats, roster_df, &player_ids, league.scoring)?;
+    let best = lineup::optimal_lineup(&actual, &league.slots);
+
+    let (chosen, mut lineup_df) = match projections {
+        Some(path) => {
+            let projected = project(&actual, path, week)?;
+            let picked = lineup::optimal_lineup(&projected, &league.slots);
+            let chosen = picked.rescore(&actual);
+            let mut df = picked.to_df()?;
+            df.rename("points", "projected_points")?;
+            df.with_column(chosen.to_df()?.column("points")?.clone())?;
+            (chosen, df)
+        }
+        None => (best.clone(), best.to_df()?),
+    };
+    lineup_df.rename("points", "actual_points")?;
+    print_df(output, lineup_df, all())?;
+
+    // The totals are only for reading, so they're left out of structured output
+    if output.format != OutputFormat::Table || output.file.is_some() {
+        return Ok(());
+    }
+
+    let (label, lineup_points) = if started_ids.is_empty() {
+        ("lineup", chosen.points())
+    } else {
+        let started_points = actual
+            .iter()
+            .filter(|player| started_ids.contains(&player.player_id))
+            .fold(0.0, |total, player| total + player.points);
+        ("started", started_points)
+    };
+    let roster_points = best.points() + best.bench_points();
+    // Points left on the bench are how far the lineup fell short of the optimal one
+    let summary = df!(
+        "lineup" => [label, "optimal"],
+        "points" => [lineup_points, best.points()],
+        "bench_points" => [roster_points - lineup_points, best.bench_points()],
+        "points_left_on_bench" => [best.points() - lineup_points, 0.0],
+    )?;
+    print_df(output, summary, all())
+}
+
+/// Swaps each player's actual points for their projected points for the week
+fn project(
+    actual: &[lineup::LineupPlayer],
+    path: &Path,
+    week: u16,
+) -> Result<Vec<lineup::LineupPlayer>> {
+    let mut lf = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("parquet") => fff::scan_parquets(&[path])?,
+        Some("csv") => LazyCsvReader::new(path).with_has_header(true).finish()?,
+        _ => bail!("Projections must be a .csv or .parquet file"),
+    };
+    if lf.schema()?.contains("week") {
+        lf = lf.filter(col("week").cast(DataType::UInt32).eq(lit(week as u32)));
+    }
+    let df = lf
+        .select([
+            col("player_id").cast(DataType::String),
+            col("projected_points").cast(DataType::Float64),
+        ])
+        .collect()
+        .with_context(|| format!("Failed to read projections from {}", path.display()))?;
+
+    let ids = df.column("player_id")?.str()?;
+    let points = df.column("projected_points")?.f64()?;
+    let projected: HashMap<&str, f64> = ids
+        .into_iter()
+        .zip(points)
+        .filter_map(|(id, points)| Some((id?, points?)))
+        .collect();
+    let players = actual
+        .iter()
+        .map(|player| {
+            let points = projected.get(player.player_id.as_str()).copied();
+            if points.is_none() {
+                log::warn!("No projection for {}", player.player_id);
+            }
+            lineup::LineupPlayer {
+                points: points.unwrap_or(0.0),
+                ..player.clone()
+            }
+        })
+        .collect();
+    Ok(players)
+}
+
+fn game(
+    source: &DataSource,
+    output: &Output,
+    years: &[u16],
+    game_id: &str,
+    scoring: Scoring,
+    exclude: &ExcludeArgs,
+) -> Result<()> {
+    // Game IDs start with their season, so use that instead of --years when it parses
+    let season = game_id
+        .split('_')
+        .next()
+        .and_then(|s| s.parse::<u16>().ok());
+    let years = season.map_or_else(|| years.to_vec(), |season| vec![season]);
+
+    let game_df =
+        PbpDf::load_seasons(source, &years)?.filter(PbpFilter::new().game(game_id).build())?;
+    let first_play = game_df.clone().lazy().limit(1).collect()?;
+    if first_play.height() == 0 {
+        bail!("No plays found for game {}", game_id);
+    }
+
+    let scores = game_df
+        .fantasy_stats()?
+        .exclude(&exclude.excluded_ids(source, &years)?)?
+        .score(scoring)?;
+    let print_cols = cols(["week", "team", "player_id", "player_name", "fantasy_points"]);
+    print_df(output, scores, print_cols)
+}
+
+fn explain(
+    source: &DataSource,
+    output: &Output,
+    years: &[u16],
+    player: &str,
+    weeks: Option<WeekArg>,
+    scoring: Scoring,
+    exclude: &ExcludeArgs,
+) -> Result<()> {
+    let lookup = player_lookup(source, years)?;
+    let player = lookup.resolve(player)?;
+    if exclude.resolve(&lookup)?.contains(&player.gsis_id) {
+        bail!("{} is excluded", player);
     }
+    let pbp_df = PbpDf::load_seasons(source, years)?;
+    explain_player(output, pbp_df, player, weeks, scoring)
+}
+
+/// Prints a player's itemized points and, for tables, the plays they came from
+fn explain_player(
+    output: &Output,
+    pbp_df: PbpDf,
+    player: &Player,
+    weeks: Option<WeekArg>,
+    scoring: Scoring,
+) -> Result<()> {
+    // Show every play and the full description
+    let output = &Output {
+        full_table: true,
+        ..output.clone()
+    };
 
+    let pbp_filter = match weeks {
+        Some(WeekArg::Week(week)) => PbpFilter::new().week(week),
+        Some(WeekArg::WeekRange(week, through)) => PbpFilter::new().week_range(week, through),
+        None => PbpFilter::new(),
+    };
+
+    let pbp_df = pbp_df.filter(pbp_filter.build())?;
+
+    // Stats are computed over all plays so that every player involved is credited correctly
+    let breakdown = pbp_df
+        .clone()
+        .fantasy_stats()?
+        .score_breakdown(scoring)?
+        .lazy()
+        .filter(col("player_id").eq(lit(player.gsis_id.as_str())))
+        .collect()?;
+    if breakdown.height() == 0 {
+        bail!("No fantasy points found for {}", player);
+    }
+    print_breakdown(output, &breakdown)?;
+
+    // The plays are only for reading, so they're left out of structured output
+    if output.format != OutputFormat::Table || output.file.is_some() {
+        return Ok(());
+    }
+
+    let plays_df = pbp_df
+        .filter(PbpFilter::new().player_id(&player.gsis_id).build())?
+        .collect()?;
+    log::info!("Plays involving {}", player);
+    print_df(
+        output,
+        plays_df,
+        cols(["game_id", "week", "qtr", "time", "desc"]),
+    )?;
     Ok(())
 }
 
-fn debug_df(df: &DataFrame, print_cols: &[&str]) -> Result<()> {
-    let print_df = df.clone().lazy().select([cols(print_cols)]).collect()?;
-    log::debug!("{}", print_df);
+fn query(
+    source: &DataSource,
+    output: &Output,
+    years: &[u16],
+    sql: &str,
+    scoring: Scoring,
+    excluded_ids: &[String],
+) -> Result<()> {
+    let pbp_df = PbpDf::load_seasons_with_all_columns(source, years)?;
+    let roster_df = RosterDf::load_seasons(source, years)?;
+    let fantasy_stats = pbp_df.clone().fantasy_stats()?.exclude(excluded_ids)?;
+    let mut ctx = query_context(pbp_df, roster_df, fantasy_stats, scoring)?;
+    let df = query_sql(&mut ctx, sql)?;
+    output.write(df)
+}
+
+fn data(source: &DataSource, output: &Output, write: bool) -> Result<()> {
+    let manifest = source.manifest()?;
+    log::info!("Data directory: {}", source.dir().display());
+    print_df(output, manifest.to_df()?, all())?;
+
+    if write {
+        let path = source.dir().join(MANIFEST_FILE);
+        manifest.save(&path)?;
+        log::info!("Wrote {}", path.display());
+    }
     Ok(())
 }
 
-fn print_df(df: &DataFrame, print_cols: Expr) -> Result<()> {
-    let print_df = df.clone().lazy().select([print_cols]).collect()?;
-    println!("{}", print_df);
+/// Prints the non-zero scoring components of each game as rows
+fn print_breakdown(output: &Output, df: &DataFrame) -> Result<()> {
+    let mut game_ids = Vec::new();
+    let mut components = Vec::new();
+    let mut points = Vec::new();
+
+    let games = df.column("game_id")?.str()?;
+    for name in df.get_column_names() {
+        if !name.ends_with("_pts") && name != "fantasy_points" {
+            continue;
+        }
+        let values = df.column(name)?.f64()?;
+        for (game_id, value) in games.into_iter().zip(values) {
+            if let (Some(game_id), Some(value)) = (game_id, value) {
+                if value != 0.0 || name == "fantasy_points" {
+                    game_ids.push(game_id);
+                    components.push(name);
+                    points.push(value);
+                }
+            }
+        }
+    }
+
+    let itemized = df!(
+        "game_id" => game_ids,
+        "component" => components,
+        "points" => points,
+    )?
+    .sort(
+        ["game_id"],
+        SortMultipleOptions::default().with_maintain_order(true),
+    )?;
+    output.write(itemized)?;
     Ok(())
 }
+
+fn preset_scoring(score: &str) -> Result<Scoring> {
+    let scoring = match score {
+        "shawn" => Scoring::from_toml(SHAWN_SCORING)?,
+        _ => match score.parse::<Preset>() {
+            Ok(preset) => preset.scoring(),
+            Err(_) => bail!("Unsupported scoring. Use: ppr, half-ppr, no-ppr, or shawn"),
+        },
+    };
+    Ok(scoring)
+}
+
+fn debug_df(df: DataFrame, print_cols: &[&str]) -> Result<()> {
+    let print_df = df.lazy().select([cols(print_cols)]).collect()?;
+    log::debug!("{}", print_df);
+    Ok(())
+}
+
+fn print_df(output: &Output, df: DataFrame, print_cols: Expr) -> Result<()> {
+    let print_df = df.lazy().select([print_cols]).collect()?;
+    output.write(print_df)
+}
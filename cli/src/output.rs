@@ -0,0 +1,121 @@
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+use clap::ValueEnum;
+use comfy_table::{presets::UTF8_FULL_CONDENSED, ContentArrangement, Table};
+use parse_display::Display;
+use polars::prelude::*;
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum, Display)]
+#[display(style = "lowercase")]
+pub enum OutputFormat {
+    /// Pretty-printed table (truncated to fit the terminal)
+    #[default]
+    Table,
+    Csv,
+    /// A single JSON array of row objects
+    Json,
+    /// One JSON object per line
+    Ndjson,
+    Parquet,
+    Markdown,
+}
+
+/// Where and how results are written
+#[derive(Clone, Debug, Default)]
+pub struct Output {
+    pub format: OutputFormat,
+    pub file: Option<PathBuf>,
+    /// Print every row and the full text of each cell in tables, instead of truncating them
+    pub full_table: bool,
+}
+
+impl Output {
+    pub fn write(&self, mut df: DataFrame) -> Result<()> {
+        let mut writer: Box<dyn Write> = match &self.file {
+            Some(path) => Box::new(
+                File::create(path)
+                    .with_context(|| format!("Failed to create {}", path.display()))?,
+            ),
+            None if self.format == OutputFormat::Parquet => {
+                bail!("Parquet output is binary; use --out-file to choose where to write it")
+            }
+            None => Box::new(io::stdout().lock()),
+        };
+
+        match self.format {
+            OutputFormat::Table if self.full_table => write_full_table(&mut writer, &df)?,
+            OutputFormat::Table => writeln!(writer, "{}", df)?,
+            OutputFormat::Csv => CsvWriter::new(&mut writer).finish(&mut df)?,
+            OutputFormat::Json => {
+                JsonWriter::new(&mut writer)
+                    .with_json_format(JsonFormat::Json)
+                    .finish(&mut df)?;
+                writeln!(writer)?;
+            }
+            OutputFormat::Ndjson => JsonWriter::new(&mut writer)
+                .with_json_format(JsonFormat::JsonLines)
+                .finish(&mut df)?,
+            OutputFormat::Parquet => {
+                ParquetWriter::new(&mut writer).finish(&mut df)?;
+            }
+            OutputFormat::Markdown => write_markdown(&mut writer, &df)?,
+        }
+        writer.flush()?;
+
+        if let Some(path) = &self.file {
+            log::info!("Wrote {} rows to {}", df.height(), path.display());
+        }
+        Ok(())
+    }
+}
+
+/// Writes a table like polars' own, but with every row and untruncated cells
+fn write_full_table(writer: &mut impl Write, df: &DataFrame) -> Result<()> {
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL_CONDENSED)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(
+            df.get_columns()
+                .iter()
+                .map(|series| format!("{}\n---\n{}", series.name(), series.dtype())),
+        );
+    for i in 0..df.height() {
+        table.add_row(
+            df.get_columns()
+                .iter()
+                .map(|series| cell_text(series, i).unwrap_or_else(|| "null".to_string())),
+        );
+    }
+    writeln!(writer, "shape: {:?}", df.shape())?;
+    writeln!(writer, "{}", table)?;
+    Ok(())
+}
+
+fn write_markdown(writer: &mut impl Write, df: &DataFrame) -> Result<()> {
+    let names = df.get_column_names();
+    writeln!(writer, "| {} |", names.join(" | "))?;
+    writeln!(writer, "|{}", "---|".repeat(names.len()))?;
+
+    for i in 0..df.height() {
+        let cells: Vec<String> = df
+            .get_columns()
+            .iter()
+            .map(|series| cell_text(series, i).unwrap_or_default().replace('|', "\\|"))
+            .collect();
+        writeln!(writer, "| {} |", cells.join(" | "))?;
+    }
+    Ok(())
+}
+
+/// A cell's text, without the quotes polars puts around strings, or `None` when it's null
+fn cell_text(series: &Series, i: usize) -> Option<String> {
+    match series.get(i) {
+        Ok(AnyValue::Null) | Err(_) => None,
+        Ok(AnyValue::String(s)) => Some(s.to_string()),
+        Ok(value) => Some(value.to_string()),
+    }
+}
@@ -0,0 +1,367 @@
+use std::path::PathBuf;
+
+use anyhow::{bail, Context as _, Result};
+use clap::ValueEnum;
+use fff::{
+    data::DataSource,
+    filter::{query_context, query_sql},
+    lookup::PlayerLookup,
+    pbp::PbpDf,
+    play::Play,
+    roster::RosterDf,
+    scoring::{FantasyStatsDf, Scoring},
+};
+use polars::{prelude::*, sql::SQLContext};
+use rustyline::{
+    completion::Completer, error::ReadlineError, highlight::Highlighter, hint::Hinter,
+    history::DefaultHistory, validate::Validator, Context, Editor, Helper,
+};
+
+use crate::{
+    explain_player, narrow_stats, output::Output, preset_scoring, print_scores, split_opponent,
+    ExcludeArgs, FilterArgs, ScoreBy, WeekArg,
+};
+
+const HISTORY_FILE: &str = ".fff_history";
+
+const HELP: &str = "\
+Anything that isn't a command below runs as SQL against the plays, rosters, and fantasy tables.
+
+  score [preset] [by player|player-game|player-season|player-opponent|game] [week 1-5]
+        [team KC] [vs BUF] [pos wr]
+  explain <player> [week 1-5]
+  .tables                List tables with their row counts
+  .schema <table>        List a table's columns and types
+  .scoring <preset>      Change the scoring used by the fantasy table
+  .help                  Show this message
+  .quit                  Exit (or Ctrl-D)";
+
+const COMMANDS: [&str; 8] = [
+    "score", "explain", ".tables", ".schema", ".scoring", ".help", ".quit", ".exit",
+];
+
+const SQL_KEYWORDS: [&str; 16] = [
+    "select", "from", "where", "group", "by", "order", "limit", "join", "on", "and", "or", "as",
+    "sum", "count", "avg", "desc",
+];
+
+/// Data loaded once for the whole session, so repeated queries don't reread parquet files
+struct Session {
+    output: Output,
+    scoring: Scoring,
+    pbp_df: PbpDf,
+    roster_df: RosterDf,
+    fantasy_stats: FantasyStatsDf,
+    lookup: PlayerLookup,
+    /// Players left out of the fantasy table and commands
+    excluded_ids: Vec<String>,
+    ctx: SQLContext,
+}
+
+impl Session {
+    fn load(
+        source: &DataSource,
+        years: &[u16],
+        output: Output,
+        scoring: Scoring,
+        exclude: &ExcludeArgs,
+    ) -> Result<Self> {
+        // Every column, since the plays table is for ad-hoc SQL
+        let pbp_df = PbpDf::load_seasons_with_all_columns(source, years)?.cache()?;
+        let roster_df = RosterDf::load_seasons(source, years)?.cache()?;
+        let lookup = PlayerLookup::load(roster_df.clone(), pbp_df.clone())?;
+        let excluded_ids = exclude.resolve(&lookup)?;
+        let fantasy_stats = pbp_df
+            .clone()
+            .fantasy_stats()?
+            .exclude(&excluded_ids)?
+            .cache()?;
+        let ctx = query_context(
+            pbp_df.clone(),
+            roster_df.clone(),
+            fantasy_stats.clone(),
+            scoring,
+        )?;
+
+        Ok(Self {
+            output,
+            scoring,
+            pbp_df,
+            roster_df,
+            fantasy_stats,
+            lookup,
+            excluded_ids,
+            ctx,
+        })
+    }
+
+    /// Runs one line of input, returning false when the session should end
+    fn run(&mut self, line: &str) -> Result<bool> {
+        let mut words = line.split_whitespace();
+        match words.next() {
+            None => {}
+            Some(".quit" | ".exit") => return Ok(false),
+            Some(".help") => println!("{}", HELP),
+            Some(".tables") => self.tables()?,
+            Some(".schema") => match words.next() {
+                Some(table) => self.schema(table)?,
+                None => bail!("Usage: .schema <table>"),
+            },
+            Some(".scoring") => match words.next() {
+                Some(preset) => {
+                    self.scoring = preset_scoring(preset)?;
+                    self.ctx = query_context(
+                        self.pbp_df.clone(),
+                        self.roster_df.clone(),
+                        self.fantasy_stats.clone(),
+                        self.scoring,
+                    )?;
+                }
+                None => bail!("Usage: .scoring <preset>"),
+            },
+            Some("score") => self.score(&words.collect::<Vec<_>>())?,
+            Some("explain") => self.explain(&words.collect::<Vec<_>>())?,
+            Some(_) => {
+                let df = query_sql(&mut self.ctx, line)?;
+                self.output.write(df)?;
+            }
+        }
+        Ok(true)
+    }
+
+    fn tables(&mut self) -> Result<()> {
+        let mut names = self.ctx.get_tables();
+        names.sort();
+
+        let mut rows = Vec::new();
+        let mut columns = Vec::new();
+        for name in &names {
+            let mut lf = self.ctx.execute(&format!("select * from {}", name))?;
+            columns.push(lf.schema()?.len() as u64);
+            let count = lf.select([len()]).collect()?;
+            rows.push(count.column("len")?.u32()?.get(0).unwrap_or(0) as u64);
+        }
+
+        let df = df!(
+            "table" => names,
+            "rows" => rows,
+            "columns" => columns,
+        )?;
+        self.output.write(df)
+    }
+
+    fn schema(&mut self, table: &str) -> Result<()> {
+        let schema = self
+            .ctx
+            .execute(&format!("select * from {}", table))?
+            .schema()?;
+        let df = df!(
+            "column" => schema.iter_names().map(|name| name.as_str()).collect::<Vec<_>>(),
+            "type" => schema.iter_dtypes().map(|dtype| dtype.to_string()).collect::<Vec<_>>(),
+        )?;
+        self.output.write(df)
+    }
+
+    /// `score [preset] [by <score-by>] [week <weeks>] [team <team>] [vs <team>] [pos <position>]`
+    fn score(&self, words: &[&str]) -> Result<()> {
+        let mut scoring = self.scoring;
+        let mut score_by = ScoreBy::Player;
+        let mut filters = FilterArgs::default();
+
+        let mut words = words.iter().copied();
+        while let Some(word) = words.next() {
+            let mut value = || {
+                words
+                    .next()
+                    .with_context(|| format!("Expected a value after '{}'", word))
+            };
+            match word {
+                "by" => {
+                    let value = value()?;
+                    score_by = ScoreBy::from_str(value, true)
+                        .map_err(|_| anyhow::anyhow!("Unknown score-by '{}'", value))?;
+                }
+                "week" | "weeks" => {
+                    let value = value()?;
+                    let weeks = value
+                        .parse::<WeekArg>()
+                        .with_context(|| format!("Invalid week '{}'", value))?;
+                    filters.weeks = Some(weeks);
+                }
+                "team" => filters.team = Some(value()?.to_uppercase()),
+                "vs" => filters.vs = Some(value()?.to_uppercase()),
+                "pos" => {
+                    let value = value()?;
+                    let position = value
+                        .parse()
+                        .map_err(|_| anyhow::anyhow!("Unknown position '{}'", value))?;
+                    filters.position = Some(position);
+                }
+                preset => scoring = preset_scoring(preset)?,
+            }
+        }
+
+        let (filters, opponent) = split_opponent(&filters, score_by);
+        let fantasy_stats = narrow_stats(self.fantasy_stats.clone(), &filters, &[], || {
+            Ok(self.roster_df.clone())
+        })?;
+        print_scores(
+            &self.output,
+            fantasy_stats,
+            scoring,
+            score_by,
+            opponent.as_deref(),
+        )
+    }
+
+    /// `explain <player> [week <weeks>]`, where the player can be several words
+    fn explain(&self, words: &[&str]) -> Result<()> {
+        let (player, weeks) = match words {
+            [player @ .., "week" | "weeks", weeks] if !player.is_empty() => {
+                let weeks = weeks
+                    .parse::<WeekArg>()
+                    .with_context(|| format!("Invalid week '{}'", weeks))?;
+                (player, Some(weeks))
+            }
+            [] => bail!("Usage: explain <player> [week <weeks>]"),
+            player => (player, None),
+        };
+        let player = self.lookup.resolve(&player.join(" "))?;
+        if self.excluded_ids.contains(&player.gsis_id) {
+            bail!("{} is excluded", player);
+        }
+        explain_player(
+            &self.output,
+            self.pbp_df.clone(),
+            player,
+            weeks,
+            self.scoring,
+        )
+    }
+}
+
+/// Completes commands, table names, SQL keywords, and column names
+struct ReplHelper {
+    words: Vec<String>,
+}
+
+impl ReplHelper {
+    fn new(session: &mut Session) -> Result<Self> {
+        let mut words: Vec<String> = COMMANDS
+            .iter()
+            .chain(&SQL_KEYWORDS)
+            .chain(&Play::FIELD_NAMES)
+            .map(|word| word.to_string())
+            .collect();
+
+        // Columns computed by fff (e.g. fantasy_points) aren't part of the Play schema
+        for table in session.ctx.get_tables() {
+            let schema = session
+                .ctx
+                .execute(&format!("select * from {}", table))?
+                .schema()?;
+            words.extend(schema.iter_names().map(|name| name.to_string()));
+            words.push(table);
+        }
+        words.sort();
+        words.dedup();
+        Ok(Self { words })
+    }
+}
+
+impl Completer for ReplHelper {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        let is_word = |c: char| c.is_alphanumeric() || c == '_' || c == '.';
+        let start = line[..pos]
+            .char_indices()
+            .rev()
+            .find(|(_, c)| !is_word(*c))
+            .map_or(0, |(i, c)| i + c.len_utf8());
+        let prefix = &line[start..pos];
+        if prefix.is_empty() {
+            return Ok((start, Vec::new()));
+        }
+
+        let candidates = self
+            .words
+            .iter()
+            .filter(|word| word.starts_with(prefix))
+            .cloned()
+            .collect();
+        Ok((start, candidates))
+    }
+}
+
+impl Hinter for ReplHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ReplHelper {}
+
+impl Validator for ReplHelper {}
+
+impl Helper for ReplHelper {}
+
+/// History is kept in the home directory when there is one, otherwise in the data directory
+fn history_path(source: &DataSource) -> PathBuf {
+    std::env::var_os("HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| source.dir().to_path_buf())
+        .join(HISTORY_FILE)
+}
+
+pub fn run(
+    source: &DataSource,
+    years: &[u16],
+    output: Output,
+    scoring: Scoring,
+    exclude: &ExcludeArgs,
+) -> Result<()> {
+    let mut session = Session::load(source, years, output, scoring, exclude)?;
+
+    let mut editor = Editor::<ReplHelper, DefaultHistory>::new()?;
+    editor.set_helper(Some(ReplHelper::new(&mut session)?));
+    let history = history_path(source);
+    // There's no history yet on the first run
+    let _ = editor.load_history(&history);
+
+    println!(
+        "Loaded {}. Type .help for commands.",
+        years
+            .iter()
+            .map(u16::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    loop {
+        let line = match editor.readline("fff> ") {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(err) => return Err(err.into()),
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        editor.add_history_entry(line)?;
+
+        match session.run(line) {
+            Ok(true) => {}
+            Ok(false) => break,
+            Err(err) => eprintln!("Error: {:#}", err),
+        }
+    }
+
+    if let Err(err) = editor.save_history(&history) {
+        log::warn!("Failed to save history to {}: {}", history.display(), err);
+    }
+    Ok(())
+}
@@ -0,0 +1,15 @@
+use crate::{error::Error, Result};
+use serde_json::Value;
+use std::path::Path;
+
+/// Reads a TOML or JSON config file (chosen by extension) into a generic value
+pub(crate) fn read_value<P: AsRef<Path>>(path: P) -> Result<Value> {
+    let path = path.as_ref();
+    let contents = std::fs::read_to_string(path)?;
+    let value = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("toml") => toml::from_str(&contents)?,
+        Some("json") => serde_json::from_str(&contents)?,
+        _ => return Err(Error::UnsupportedConfigFormat(path.to_path_buf())),
+    };
+    Ok(value)
+}
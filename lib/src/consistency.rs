@@ -0,0 +1,304 @@
+use crate::{
+    roster::RosterDf,
+    scoring::{self, FantasyStatsDf, Scoring},
+    Result,
+};
+use polars::prelude::*;
+use std::collections::HashMap;
+
+/// Weekly scores above `boom` are booms and below `bust` are busts
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Thresholds {
+    pub boom: f64,
+    pub bust: f64,
+}
+
+/// Roughly a top-5 week (boom) and an unstartable one (bust) at each position in PPR scoring
+pub const DEFAULT_THRESHOLDS: [(&str, Thresholds); 9] = [
+    (
+        "QB",
+        Thresholds {
+            boom: 25.0,
+            bust: 12.0,
+        },
+    ),
+    (
+        "RB",
+        Thresholds {
+            boom: 20.0,
+            bust: 8.0,
+        },
+    ),
+    (
+        "WR",
+        Thresholds {
+            boom: 20.0,
+            bust: 8.0,
+        },
+    ),
+    (
+        "TE",
+        Thresholds {
+            boom: 15.0,
+            bust: 5.0,
+        },
+    ),
+    (
+        "K",
+        Thresholds {
+            boom: 12.0,
+            bust: 4.0,
+        },
+    ),
+    (
+        "DST",
+        Thresholds {
+            boom: 12.0,
+            bust: 3.0,
+        },
+    ),
+    (
+        "DL",
+        Thresholds {
+            boom: 10.0,
+            bust: 3.0,
+        },
+    ),
+    (
+        "LB",
+        Thresholds {
+            boom: 10.0,
+            bust: 3.0,
+        },
+    ),
+    (
+        "DB",
+        Thresholds {
+            boom: 10.0,
+            bust: 3.0,
+        },
+    ),
+];
+
+/// [`DEFAULT_THRESHOLDS`] adjusted to `scoring`: each position's are multiplied by the points it
+/// scores under `scoring` relative to PPR over all the stats, so e.g. receivers' drop in half PPR.
+/// Positions without PPR points (individual defensive players) keep the defaults.
+pub fn scaled_thresholds(
+    fantasy_stats: FantasyStatsDf,
+    roster_df: RosterDf,
+    scoring: Scoring,
+) -> Result<Vec<(&'static str, Thresholds)>> {
+    let lf = fantasy_stats
+        .lazy()
+        .filter(col("player_id").is_not_null())
+        .select([
+            col("player_id"),
+            col("team"),
+            scoring::scoring_cols(scoring).alias("fantasy_points"),
+            scoring::scoring_cols(Scoring::ppr()).alias("ppr_points"),
+        ]);
+    let totals = roster_df
+        .add_positions(lf)?
+        .group_by([col("position")])
+        .agg([cols(["fantasy_points", "ppr_points"]).sum()])
+        .filter(col("ppr_points").gt(lit(0.0)))
+        .collect()?;
+
+    let positions = totals.column("position")?.str()?;
+    let points = totals.column("fantasy_points")?.f64()?;
+    let ppr_points = totals.column("ppr_points")?.f64()?;
+    let factors: HashMap<&str, f64> = positions
+        .into_iter()
+        .zip(points.into_iter().zip(ppr_points))
+        .filter_map(|(position, (points, ppr_points))| Some((position?, points? / ppr_points?)))
+        .collect();
+
+    let thresholds = DEFAULT_THRESHOLDS
+        .into_iter()
+        .map(|(position, thresholds)| {
+            let factor = factors.get(position).copied().unwrap_or(1.0);
+            let thresholds = Thresholds {
+                boom: thresholds.boom * factor,
+                bust: thresholds.bust * factor,
+            };
+            (position, thresholds)
+        })
+        .collect();
+    Ok(thresholds)
+}
+
+/// How each player's weekly scores are distributed, to tell steady starters from volatile ones.
+///
+/// `scores` are player-games as returned by `FantasyStatsDf::score`. Besides the mean, median, and
+/// standard deviation, each player gets:
+///
+/// - `cv`: the coefficient of variation (std / mean), comparable across players who score more or
+///   less; lower is steadier
+/// - `floor` and `ceiling`: their 10th and 90th percentile weeks
+/// - `boom_rate` and `bust_rate`: the share of weeks above or below the thresholds for their
+///   position (see [`scaled_thresholds`])
+pub fn consistency(
+    scores: DataFrame,
+    roster_df: RosterDf,
+    thresholds: &[(&str, Thresholds)],
+) -> Result<DataFrame> {
+    let thresholds = df!(
+        "position" => thresholds.iter().map(|(position, _)| *position).collect::<Vec<_>>(),
+        "boom_threshold" => thresholds.iter().map(|(_, t)| t.boom).collect::<Vec<_>>(),
+        "bust_threshold" => thresholds.iter().map(|(_, t)| t.bust).collect::<Vec<_>>(),
+    )?;
+
+    let points = || col("fantasy_points");
+    let percentile =
+        |quantile: f64| points().quantile(lit(quantile), QuantileInterpolOptions::Linear);
+    let rate = |weeks: Expr| weeks.cast(DataType::Float64).mean();
+    let df = roster_df
+        .add_positions(scores.lazy().filter(col("player_id").is_not_null()))?
+        .join(
+            thresholds.lazy(),
+            [col("position")],
+            [col("position")],
+            JoinArgs::new(JoinType::Left),
+        )
+        .group_by([col("player_id")])
+        .agg([
+            cols(["player_name", "team", "position"]).last(),
+            col("game_id").n_unique().alias("games"),
+            points().mean().alias("mean"),
+            points().median().alias("median"),
+            points().std(1).alias("std"),
+            percentile(0.1).alias("floor"),
+            percentile(0.9).alias("ceiling"),
+            rate(points().gt(col("boom_threshold"))).alias("boom_rate"),
+            rate(points().lt(col("bust_threshold"))).alias("bust_rate"),
+        ])
+        // A mean at or below zero makes the ratio meaningless
+        .with_column(
+            when(col("mean").gt(lit(0.0)))
+                .then(col("std") / col("mean"))
+                .otherwise(lit(NULL))
+                .alias("cv"),
+        )
+        .select([
+            col("player_id"),
+            col("player_name"),
+            col("team"),
+            col("position"),
+            col("games"),
+            col("mean"),
+            col("median"),
+            col("std"),
+            col("cv"),
+            col("floor"),
+            col("ceiling"),
+            col("boom_rate"),
+            col("bust_rate"),
+        ])
+        .sort(
+            ["mean"],
+            SortMultipleOptions::default().with_order_descending(true),
+        )
+        .collect()?;
+    Ok(df)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pbp;
+    use serde_json::json;
+
+    fn roster_df() -> RosterDf {
+        RosterDf::new(
+            df!(
+                "gsis_id" => ["QB1", "RB1", "WR1"],
+                "position" => ["QB", "RB", "WR"],
+                "team" => ["KC", "KC", "KC"],
+            )
+            .unwrap()
+            .lazy(),
+        )
+    }
+
+    fn thresholds(thresholds: &[(&str, Thresholds)], position: &str) -> Thresholds {
+        thresholds
+            .iter()
+            .find(|(p, _)| *p == position)
+            .map(|(_, t)| *t)
+            .unwrap()
+    }
+
+    fn value(df: &DataFrame, player_id: &str, name: &str) -> Option<f64> {
+        let df = df
+            .clone()
+            .lazy()
+            .filter(col("player_id").eq(lit(player_id)))
+            .collect()
+            .unwrap();
+        assert_eq!(df.height(), 1, "{player_id}");
+        df.column(name)
+            .unwrap()
+            .cast(&DataType::Float64)
+            .unwrap()
+            .f64()
+            .unwrap()
+            .get(0)
+    }
+
+    #[test]
+    fn scales_thresholds_by_points_relative_to_ppr() {
+        let fantasy_stats = pbp::tests::plays(&[
+            json!({
+                "play_type": "pass", "passer_player_id": "QB1", "passer_player_name": "QB1",
+                "receiver_player_id": "WR1", "receiver_player_name": "WR1", "complete_pass": 1.0,
+                "passing_yards": 10.0, "receiving_yards": 10.0,
+            }),
+            json!({
+                "play_type": "run", "rusher_player_id": "RB1", "rusher_player_name": "RB1",
+                "rushing_yards": 100.0,
+            }),
+        ])
+        .fantasy_stats()
+        .unwrap();
+        let scaled = scaled_thresholds(fantasy_stats, roster_df(), Scoring::half_ppr()).unwrap();
+
+        // 1.5 of WR1's 2 PPR points
+        let wr = thresholds(&scaled, "WR");
+        assert_eq!(
+            wr,
+            Thresholds {
+                boom: 15.0,
+                bust: 6.0
+            }
+        );
+        for position in ["QB", "RB", "LB"] {
+            assert_eq!(
+                thresholds(&scaled, position),
+                thresholds(&DEFAULT_THRESHOLDS, position),
+                "{position}"
+            );
+        }
+    }
+
+    #[test]
+    fn rates_booms_and_busts_against_the_position_thresholds() {
+        let scores = df!(
+            "game_id" => ["G1", "G2", "G3", "G4", "G1", "G2"],
+            "player_id" => ["WR1", "WR1", "WR1", "WR1", "RB1", "RB1"],
+            "player_name" => ["WR1", "WR1", "WR1", "WR1", "RB1", "RB1"],
+            "team" => ["KC"; 6],
+            "fantasy_points" => [4.0, 10.0, 12.0, 30.0, 0.0, 0.0],
+        )
+        .unwrap();
+        let df = consistency(scores, roster_df(), &DEFAULT_THRESHOLDS).unwrap();
+
+        assert_eq!(value(&df, "WR1", "games"), Some(4.0));
+        assert_eq!(value(&df, "WR1", "mean"), Some(14.0));
+        assert_eq!(value(&df, "WR1", "median"), Some(11.0));
+        // Above 20 and below 8 points
+        assert_eq!(value(&df, "WR1", "boom_rate"), Some(0.25));
+        assert_eq!(value(&df, "WR1", "bust_rate"), Some(0.25));
+        assert_eq!(value(&df, "RB1", "bust_rate"), Some(1.0));
+        assert_eq!(value(&df, "RB1", "cv"), None);
+    }
+}
@@ -0,0 +1,215 @@
+use crate::{config, error::Error, Result};
+use parse_display::{Display, FromStr};
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+/// Environment variable that overrides the default `data` directory
+pub const DATA_DIR_ENV: &str = "FFF_DATA_DIR";
+
+/// Name of the user config file, in `$XDG_CONFIG_HOME/fff` or `~/.config/fff`
+pub const CONFIG_FILE: &str = "config.toml";
+
+/// Name of the manifest file written into the data directory
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// The kinds of files that can live in the data directory
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Display, FromStr, Serialize, Deserialize,
+)]
+#[display(style = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum Dataset {
+    Pbp,
+    Rosters,
+    Ecr,
+}
+
+impl Dataset {
+    /// Whether the dataset is split into one file per season
+    pub fn is_seasonal(self) -> bool {
+        !matches!(self, Dataset::Ecr)
+    }
+
+    fn file_name(self, season: Option<u16>) -> String {
+        match season {
+            Some(season) if self.is_seasonal() => format!("{}_{}.parquet", self, season),
+            _ => format!("{}.parquet", self),
+        }
+    }
+
+    /// Parses a data file name back into its dataset and season
+    fn parse_file_name(file_name: &str) -> Option<(Dataset, Option<u16>)> {
+        let stem = file_name.strip_suffix(".parquet")?;
+        match stem.split_once('_') {
+            Some((name, season)) => {
+                let dataset = name.parse::<Dataset>().ok().filter(|d| d.is_seasonal())?;
+                Some((dataset, Some(season.parse().ok()?)))
+            }
+            None => {
+                let dataset = stem.parse::<Dataset>().ok().filter(|d| !d.is_seasonal())?;
+                Some((dataset, None))
+            }
+        }
+    }
+
+    fn download_hint(self, season: Option<u16>) -> String {
+        match (self, season) {
+            (Dataset::Ecr, _) => "just _download-ecr".to_string(),
+            (_, Some(season)) => format!("just download-data {}", season),
+            (_, None) => "just download-data <year>".to_string(),
+        }
+    }
+}
+
+/// The directory that play-by-play, roster, and ECR parquet files are loaded from
+#[derive(Clone, Debug)]
+pub struct DataSource {
+    dir: PathBuf,
+}
+
+impl DataSource {
+    pub fn new<P: Into<PathBuf>>(dir: P) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Uses `dir` (e.g. from `--data-dir` or `FFF_DATA_DIR`) if given, then the `data_dir` of the
+    /// user config file, and otherwise `data` relative to the current directory
+    pub fn locate(dir: Option<PathBuf>) -> Result<Self> {
+        if let Some(dir) = dir {
+            return Ok(Self::new(dir));
+        }
+        if let Some(path) = config_file().filter(|path| path.is_file()) {
+            if let Some(dir) = config_data_dir(&path)? {
+                log::debug!(
+                    "Using data directory {} from {}",
+                    dir.display(),
+                    path.display()
+                );
+                return Ok(Self::new(dir));
+            }
+        }
+        Ok(Self::new("data"))
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn path(&self, dataset: Dataset, season: Option<u16>) -> PathBuf {
+        self.dir.join(dataset.file_name(season))
+    }
+
+    /// Returns the path to a dataset file, or a `MissingData` error explaining how to get it
+    pub fn require(&self, dataset: Dataset, season: Option<u16>) -> Result<PathBuf> {
+        let path = self.path(dataset, season);
+        if path.is_file() {
+            Ok(path)
+        } else {
+            Err(Error::MissingData {
+                dataset,
+                path,
+                hint: dataset.download_hint(season),
+            })
+        }
+    }
+
+    /// Scans the data directory for known datasets, counting rows and hashing each file
+    pub fn manifest(&self) -> Result<Manifest> {
+        if !self.dir.is_dir() {
+            return Err(Error::MissingDataDir(self.dir.clone()));
+        }
+
+        let mut entries = Vec::new();
+        for dir_entry in std::fs::read_dir(&self.dir)? {
+            let path = dir_entry?.path();
+            let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            let Some((dataset, season)) = Dataset::parse_file_name(file_name) else {
+                continue;
+            };
+
+            // Files can be large, so they're streamed through the hash rather than read whole
+            let mut hasher = Sha256::new();
+            let bytes = std::io::copy(&mut BufReader::new(File::open(&path)?), &mut hasher)?;
+            let sha256 = format!("{:x}", hasher.finalize());
+            let rows = ParquetReader::new(File::open(&path)?).num_rows()?;
+            entries.push(ManifestEntry {
+                dataset,
+                season,
+                file: file_name.to_string(),
+                rows,
+                bytes,
+                sha256,
+            });
+        }
+        entries.sort_by_key(|entry| (entry.dataset, entry.season));
+
+        Ok(Manifest { entries })
+    }
+}
+
+/// The user config file, whether or not it exists
+pub fn config_file() -> Option<PathBuf> {
+    let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config_dir.join("fff").join(CONFIG_FILE))
+}
+
+/// The `data_dir` setting of a config file. Relative paths are relative to the file.
+fn config_data_dir(path: &Path) -> Result<Option<PathBuf>> {
+    let value = config::read_value(path)?;
+    match value.get("data_dir") {
+        None => Ok(None),
+        Some(serde_json::Value::String(dir)) => {
+            let base = path.parent().unwrap_or(Path::new(""));
+            Ok(Some(base.join(dir)))
+        }
+        Some(other) => Err(Error::InvalidConfig(format!(
+            "data_dir in {} must be a path, not {}",
+            path.display(),
+            other
+        ))),
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub dataset: Dataset,
+    pub season: Option<u16>,
+    pub file: String,
+    pub rows: usize,
+    pub bytes: u64,
+    pub sha256: String,
+}
+
+/// Describes the datasets present in a data directory
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Manifest {
+    pub entries: Vec<ManifestEntry>,
+}
+
+impl Manifest {
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let contents = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, contents)?;
+        Ok(())
+    }
+
+    pub fn to_df(&self) -> Result<DataFrame> {
+        let df = df!(
+            "dataset" => self.entries.iter().map(|e| e.dataset.to_string()).collect::<Vec<_>>(),
+            "season" => self.entries.iter().map(|e| e.season.map(u32::from)).collect::<Vec<_>>(),
+            "file" => self.entries.iter().map(|e| e.file.as_str()).collect::<Vec<_>>(),
+            "rows" => self.entries.iter().map(|e| e.rows as u64).collect::<Vec<_>>(),
+            "bytes" => self.entries.iter().map(|e| e.bytes).collect::<Vec<_>>(),
+            "sha256" => self.entries.iter().map(|e| e.sha256.as_str()).collect::<Vec<_>>(),
+        )?;
+        Ok(df)
+    }
+}
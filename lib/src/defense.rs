@@ -0,0 +1,172 @@
+use crate::{
+    roster::RosterDf,
+    scoring::{FantasyStatsDf, Scoring},
+    Result,
+};
+use polars::prelude::*;
+
+/// Positions a defense is ranked against, as they appear on the roster
+const POSITIONS: [&str; 5] = ["QB", "RB", "WR", "TE", "K"];
+
+/// Fantasy points each defense allowed to each position per game, ranked so that 1 is the most
+/// points allowed (the best matchup for that position).
+///
+/// `over_avg_per_game` adjusts for the quality of the opposing players: it is how many points per
+/// game they scored above their own season average, so a defense that faced only elite receivers
+/// isn't penalized for it. Season averages always use every loaded game, while `weeks` (e.g. the
+/// last 4 weeks) only narrows the games counted against each defense.
+pub fn points_allowed(
+    fantasy_stats: FantasyStatsDf,
+    roster_df: RosterDf,
+    scoring: Scoring,
+    weeks: Option<(u16, u16)>,
+) -> Result<DataFrame> {
+    let positions = Series::new("positions", POSITIONS);
+    let mut lf = fantasy_stats
+        .merge_roster(roster_df.unique_players()?)?
+        .score_lazy(scoring)?
+        .filter(col("position").is_in(lit(positions)))
+        .with_column(
+            (col("fantasy_points")
+                - col("fantasy_points")
+                    .mean()
+                    .over([col("player_id"), col("season")]))
+            .alias("over_avg"),
+        );
+
+    if let Some((start, end)) = weeks {
+        let expr = col("week").is_between(start as u32, end as u32, ClosedInterval::Both);
+        lf = lf.filter(expr);
+    }
+
+    let per_game = |name: &str| col(name) / col("games").cast(DataType::Float64);
+    let rank = |name: &str| {
+        col(name)
+            .rank(
+                RankOptions {
+                    method: RankMethod::Min,
+                    descending: true,
+                },
+                None,
+            )
+            .over([col("position")])
+    };
+    let df = lf
+        .rename(["opponent"], ["defteam"])
+        // Games the defense played, even if a position didn't score against them in one
+        .with_column(
+            col("game_id")
+                .n_unique()
+                .over([col("defteam")])
+                .alias("games"),
+        )
+        .group_by([col("defteam"), col("position")])
+        .agg([
+            col("games").first(),
+            col("player_id").n_unique().alias("players"),
+            col("fantasy_points").sum().alias("points_allowed"),
+            col("over_avg").sum(),
+        ])
+        .with_columns([
+            per_game("points_allowed").alias("points_per_game"),
+            per_game("over_avg").alias("over_avg_per_game"),
+        ])
+        .with_columns([
+            rank("points_per_game").alias("rank"),
+            rank("over_avg_per_game").alias("adjusted_rank"),
+        ])
+        .select([
+            col("position"),
+            col("rank"),
+            col("defteam"),
+            col("games"),
+            col("players"),
+            col("points_allowed"),
+            col("points_per_game"),
+            col("over_avg_per_game"),
+            col("adjusted_rank"),
+        ])
+        .sort(["position", "rank"], SortMultipleOptions::default())
+        .collect()?;
+    Ok(df)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pbp;
+    use serde_json::json;
+
+    fn run(week: i32, opponent: &str, player_id: &str, yards: f64) -> serde_json::Value {
+        json!({
+            "game_id": format!("2023_{:02}_{}_KC", week, opponent), "week": week,
+            "away_team": opponent, "defteam": opponent, "play_type": "run",
+            "rusher_player_id": player_id, "rusher_player_name": player_id, "rushing_yards": yards,
+        })
+    }
+
+    /// RB1 averages 15 points and RB2 40; BUF held both below their averages in week 1
+    fn points_allowed_in(weeks: Option<(u16, u16)>) -> DataFrame {
+        let fantasy_stats = pbp::tests::plays(&[
+            run(1, "BUF", "RB1", 100.0),
+            run(1, "BUF", "RB2", 300.0),
+            run(2, "DEN", "RB1", 200.0),
+            run(3, "MIA", "RB2", 500.0),
+        ])
+        .fantasy_stats()
+        .unwrap();
+        let roster_df = RosterDf::new(
+            df!("gsis_id" => ["RB1", "RB2"], "position" => ["RB", "RB"])
+                .unwrap()
+                .lazy(),
+        );
+        points_allowed(fantasy_stats, roster_df, Scoring::ppr(), weeks).unwrap()
+    }
+
+    fn value(df: &DataFrame, defteam: &str, name: &str) -> f64 {
+        let df = df
+            .clone()
+            .lazy()
+            .filter(col("defteam").eq(lit(defteam)))
+            .collect()
+            .unwrap();
+        assert_eq!(df.height(), 1, "{defteam}");
+        df.column(name)
+            .unwrap()
+            .cast(&DataType::Float64)
+            .unwrap()
+            .f64()
+            .unwrap()
+            .get(0)
+            .unwrap()
+    }
+
+    #[test]
+    fn ranks_defenses_by_points_allowed_and_adjusts_for_the_players_faced() {
+        let df = points_allowed_in(None);
+        assert_eq!(df.height(), 3);
+        assert_eq!(value(&df, "BUF", "players"), 2.0);
+        assert_eq!(value(&df, "BUF", "points_per_game"), 40.0);
+        assert_eq!(value(&df, "BUF", "over_avg_per_game"), -15.0);
+        assert_eq!(value(&df, "DEN", "over_avg_per_game"), 5.0);
+        assert_eq!(value(&df, "MIA", "over_avg_per_game"), 10.0);
+
+        assert_eq!(value(&df, "MIA", "rank"), 1.0);
+        assert_eq!(value(&df, "BUF", "rank"), 2.0);
+        assert_eq!(value(&df, "DEN", "rank"), 3.0);
+        assert_eq!(value(&df, "MIA", "adjusted_rank"), 1.0);
+        assert_eq!(value(&df, "DEN", "adjusted_rank"), 2.0);
+        assert_eq!(value(&df, "BUF", "adjusted_rank"), 3.0);
+    }
+
+    #[test]
+    fn narrows_the_games_but_not_the_averages_to_the_weeks() {
+        let df = points_allowed_in(Some((2, 3)));
+        assert_eq!(df.height(), 2);
+        assert_eq!(value(&df, "DEN", "games"), 1.0);
+        assert_eq!(value(&df, "DEN", "points_per_game"), 20.0);
+        // Still against RB1's average over weeks 1 and 2
+        assert_eq!(value(&df, "DEN", "over_avg_per_game"), 5.0);
+        assert_eq!(value(&df, "DEN", "rank"), 2.0);
+    }
+}
@@ -1,5 +1,7 @@
+use crate::data::Dataset;
 use polars::error::PolarsError;
 use std::io::Error as IoError;
+use std::path::PathBuf;
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -9,6 +11,43 @@ pub enum Error {
     #[error("IO error")]
     Io(#[from] IoError),
 
+    #[error("TOML error")]
+    Toml(#[from] toml::de::Error),
+
+    #[error("JSON error")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Missing {dataset} data: {} not found (try `{hint}`, or set --data-dir, FFF_DATA_DIR, or data_dir in ~/.config/fff/config.toml)", path.display())]
+    MissingData {
+        dataset: Dataset,
+        path: PathBuf,
+        hint: String,
+    },
+
+    #[error("Data directory {} does not exist (set --data-dir, FFF_DATA_DIR, or data_dir in ~/.config/fff/config.toml)", .0.display())]
+    MissingDataDir(PathBuf),
+
     #[error("Expected single game, found multiple: {}", .0.join(", "))]
     NotASingleGame(Vec<String>),
+
+    #[error("No player matches '{0}'")]
+    PlayerNotFound(String),
+
+    #[error("'{query}' matches several players: {}. Use a gsis ID or add a team or position, e.g. 'J.Williams DET'", candidates.join("; "))]
+    AmbiguousPlayer {
+        query: String,
+        candidates: Vec<String>,
+    },
+
+    #[error("Unsupported config format: {} (expected .toml or .json)", .0.display())]
+    UnsupportedConfigFormat(PathBuf),
+
+    #[error("Invalid scoring: {0}")]
+    InvalidScoring(String),
+
+    #[error("Invalid league: {0}")]
+    InvalidLeague(String),
+
+    #[error("Invalid config: {0}")]
+    InvalidConfig(String),
 }
@@ -0,0 +1,120 @@
+use crate::{error::Error, lookup::PlayerLookup, Result};
+use std::path::Path;
+
+/// Players to leave out of results, such as those already drafted or rostered in a league.
+///
+/// Each line of an exclusion file is a gsis ID (e.g. `00-0033873`), a player's name (e.g.
+/// `Patrick Mahomes`), or a team abbreviation for its defense (e.g. `KC`). Blank lines are
+/// ignored, and `#` starts a comment.
+#[derive(Clone, Debug, Default)]
+pub struct Exclusions {
+    entries: Vec<String>,
+}
+
+impl Exclusions {
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        Ok(Self::parse(&contents))
+    }
+
+    pub fn parse(contents: &str) -> Self {
+        let entries = contents
+            .lines()
+            .map(|line| line.split_once('#').map_or(line, |(entry, _)| entry).trim())
+            .filter(|entry| !entry.is_empty())
+            .map(String::from)
+            .collect();
+        Self { entries }
+    }
+
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Resolves entries to player IDs: gsis IDs, team abbreviations (e.g. `KC`) for defenses, and
+    /// exact player names (see `PlayerLookup::resolve_exact`). Unlike `--player`, a typo or a last
+    /// name alone doesn't guess at a player, which could leave out the wrong one. Entries that
+    /// match no player are reported as warnings, while names that match several players fail,
+    /// since a qualifier such as `Josh Allen BUF` picks the one to leave out.
+    pub fn player_ids(&self, lookup: &PlayerLookup) -> Result<Vec<String>> {
+        let mut player_ids = Vec::new();
+        for entry in &self.entries {
+            if lookup.is_team(entry) {
+                player_ids.push(entry.to_uppercase());
+                continue;
+            }
+            match lookup.resolve_exact(entry) {
+                Ok(player) => player_ids.push(player.gsis_id.clone()),
+                Err(Error::PlayerNotFound(_)) => match lookup.search(entry).first() {
+                    Some((_, player)) => log::warn!(
+                        "Excluded player '{}' not found (did you mean {}?)",
+                        entry,
+                        player
+                    ),
+                    None => log::warn!("Excluded player '{}' not found", entry),
+                },
+                Err(err) => return Err(err),
+            }
+        }
+        player_ids.sort();
+        player_ids.dedup();
+
+        log::debug!("Excluding {} players", player_ids.len());
+        Ok(player_ids)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lookup;
+
+    #[test]
+    fn parses_entries_without_comments_or_blank_lines() {
+        let exclusions = Exclusions::parse(
+            "# drafted\n\
+             Patrick Mahomes\n\
+             \n\
+             00-0036389  # Jalen Hurts\n\
+             \tKC \n\
+             #00-0033873\n",
+        );
+        assert_eq!(
+            exclusions.entries(),
+            ["Patrick Mahomes", "00-0036389", "KC"]
+        );
+        assert!(Exclusions::parse("\n# nobody\n").is_empty());
+    }
+
+    #[test]
+    fn resolves_players_and_defenses() {
+        let exclusions =
+            Exclusions::parse("patrick mahomes\nkc\n00-4\nJosh Allen BUF\nNobody Here");
+        let player_ids = exclusions.player_ids(&lookup::tests::players()).unwrap();
+        assert_eq!(player_ids, ["00-1", "00-2", "00-4", "KC"]);
+
+        let exclusions = Exclusions::parse("P.Mahomes");
+        let player_ids = exclusions.player_ids(&lookup::tests::players()).unwrap();
+        assert_eq!(player_ids, ["00-1"]);
+    }
+
+    #[test]
+    fn excludes_nobody_for_near_misses() {
+        let exclusions = Exclusions::parse("Patrick Mahomse\nmahomes\npat mahomes\nTravis Kelcey");
+        let player_ids = exclusions.player_ids(&lookup::tests::players()).unwrap();
+        assert!(player_ids.is_empty(), "{:?}", player_ids);
+    }
+
+    #[test]
+    fn fails_on_names_shared_by_several_players() {
+        let exclusions = Exclusions::parse("Josh Allen");
+        assert!(matches!(
+            exclusions.player_ids(&lookup::tests::players()),
+            Err(Error::AmbiguousPlayer { .. })
+        ));
+    }
+}
@@ -1,9 +1,34 @@
-use crate::Result;
+use crate::{
+    pbp::PbpDf,
+    roster::RosterDf,
+    scoring::{FantasyStatsDf, Scoring},
+    Result,
+};
 use polars::{prelude::*, sql::SQLContext};
 
-pub fn filter_sql(df: LazyFrame, query: &str) -> Result<LazyFrame> {
+/// Registers the tables available to ad-hoc queries:
+///
+/// - `plays`: the play-by-play data
+/// - `rosters`: weekly rosters
+/// - `fantasy`: fantasy stats per player-game, with `fantasy_points` from the given scoring
+///
+/// Tables are lazy, so only the ones a query references are read.
+pub fn query_context(
+    pbp_df: PbpDf,
+    roster_df: RosterDf,
+    fantasy_stats: FantasyStatsDf,
+    scoring: Scoring,
+) -> Result<SQLContext> {
+    let fantasy_lf = fantasy_stats.score_lazy(scoring)?;
+
     let mut ctx = SQLContext::new();
-    ctx.register("plays", df);
-    let df = ctx.execute(&query)?;
+    ctx.register("plays", pbp_df.lazy());
+    ctx.register("rosters", roster_df.lazy());
+    ctx.register("fantasy", fantasy_lf);
+    Ok(ctx)
+}
+
+pub fn query_sql(ctx: &mut SQLContext, query: &str) -> Result<DataFrame> {
+    let df = ctx.execute(query)?.collect()?;
     Ok(df)
 }
@@ -0,0 +1,123 @@
+use crate::Result;
+use polars::prelude::*;
+
+/// How much weight recent games get in `ewma`: each game counts `1 - alpha` times the one after it
+pub const DEFAULT_ALPHA: f64 = 0.3;
+
+/// Ranks players by current form rather than season totals.
+///
+/// `scores` are player-games as returned by `FantasyStatsDf::score`. Only games up to `as_of` (a
+/// week of the latest season) count, and for each player:
+///
+/// - `rolling_avg` and `rolling_median` are over their last `last` games
+/// - `ewma` weighs every game by `(1 - alpha)^n`, where n is how many games ago it was
+/// - `trend` is `rolling_avg` minus their average for the season
+pub fn form(scores: DataFrame, last: u32, as_of: Option<u32>, alpha: f64) -> Result<DataFrame> {
+    let mut lf = scores.lazy().filter(col("player_id").is_not_null());
+    if let Some(week) = as_of {
+        // Earlier seasons are entirely before the anchor week
+        lf = lf.filter(
+            col("season")
+                .lt(col("season").max())
+                .or(col("week").lt_eq(lit(week))),
+        );
+    }
+
+    let in_window = col("games_back").lt_eq(lit(last));
+    // Normalizing the weights makes starting from (1 - alpha)^1 the same as from (1 - alpha)^0
+    let weight = lit(1.0 - alpha).pow(col("games_back"));
+    let df = lf
+        .sort(
+            ["player_id", "season", "week"],
+            SortMultipleOptions::default(),
+        )
+        .with_columns([
+            // 1 for the player's latest game, 2 for the one before, and so on
+            col("fantasy_points")
+                .cum_count(true)
+                .over([col("player_id")])
+                .alias("games_back"),
+            col("season").max().alias("current_season"),
+        ])
+        .group_by([col("player_id")])
+        .agg([
+            cols(["player_name", "team"]).last(),
+            in_window.clone().sum().alias("games"),
+            col("fantasy_points")
+                .filter(in_window.clone())
+                .mean()
+                .alias("rolling_avg"),
+            col("fantasy_points")
+                .filter(in_window)
+                .median()
+                .alias("rolling_median"),
+            ((col("fantasy_points") * weight.clone()).sum() / weight.sum()).alias("ewma"),
+            col("fantasy_points")
+                .filter(col("season").eq(col("current_season")))
+                .mean()
+                .alias("season_avg"),
+        ])
+        .with_column((col("rolling_avg") - col("season_avg")).alias("trend"))
+        .sort(
+            ["rolling_avg"],
+            SortMultipleOptions::default()
+                .with_order_descending(true)
+                .with_nulls_last(true),
+        )
+        .collect()?;
+    Ok(df)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(df: &DataFrame, player_id: &str, name: &str) -> f64 {
+        let df = df
+            .clone()
+            .lazy()
+            .filter(col("player_id").eq(lit(player_id)))
+            .collect()
+            .unwrap();
+        assert_eq!(df.height(), 1, "{player_id}");
+        df.column(name)
+            .unwrap()
+            .cast(&DataType::Float64)
+            .unwrap()
+            .f64()
+            .unwrap()
+            .get(0)
+            .unwrap()
+    }
+
+    #[test]
+    fn weighs_recent_games_up_to_the_anchor_week() {
+        let scores = df!(
+            "player_id" => ["P1", "P1", "P1", "P1", "P1", "P2", "P3"],
+            "player_name" => ["P1", "P1", "P1", "P1", "P1", "P2", "P3"],
+            "team" => ["KC"; 7],
+            "season" => [2022, 2023, 2023, 2023, 2023, 2023, 2023],
+            "week" => [17, 1, 2, 3, 4, 4, 1],
+            "fantasy_points" => [0.0, 10.0, 20.0, 30.0, 40.0, 50.0, 50.0],
+        )
+        .unwrap();
+        let df = form(scores, 2, Some(3), 0.5).unwrap();
+
+        // P2 only played after week 3
+        assert_eq!(df.height(), 2);
+        assert_eq!(
+            df.column("player_id").unwrap().str().unwrap().get(0),
+            Some("P3")
+        );
+        assert_eq!(value(&df, "P1", "games"), 2.0);
+        assert_eq!(value(&df, "P1", "rolling_avg"), 25.0);
+        assert_eq!(value(&df, "P1", "rolling_median"), 25.0);
+        // Last season's game still counts toward the EWMA, with the least weight
+        let ewma = (30.0 / 2.0 + 20.0 / 4.0 + 10.0 / 8.0)
+            / (1.0 / 2.0 + 1.0 / 4.0 + 1.0 / 8.0 + 1.0 / 16.0);
+        assert!((value(&df, "P1", "ewma") - ewma).abs() < 1e-9);
+        // But not toward the season average
+        assert_eq!(value(&df, "P1", "season_avg"), 20.0);
+        assert_eq!(value(&df, "P1", "trend"), 5.0);
+    }
+}
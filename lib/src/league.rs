@@ -0,0 +1,353 @@
+use crate::{
+    error::Error,
+    scoring::{Preset, Scoring},
+    Result,
+};
+use parse_display::Display;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+
+/// A place in a fantasy team's lineup
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Display)]
+#[display(style = "UPPERCASE")]
+pub enum Slot {
+    Qb,
+    Rb,
+    Wr,
+    Te,
+    /// RB, WR, or TE
+    Flex,
+    /// QB, RB, WR, or TE
+    Superflex,
+    K,
+    Dst,
+    /// Any individual defensive player: DL, LB, or DB
+    #[display("IDP_FLEX")]
+    IdpFlex,
+    Bench,
+    #[display("IR")]
+    Ir,
+}
+
+impl Slot {
+    /// Starting slots, from the most to the least restrictive
+    pub const STARTERS: [Slot; 9] = [
+        Slot::Qb,
+        Slot::Rb,
+        Slot::Wr,
+        Slot::Te,
+        Slot::K,
+        Slot::Dst,
+        Slot::Flex,
+        Slot::IdpFlex,
+        Slot::Superflex,
+    ];
+
+    /// Roster positions that can fill the slot. Bench and IR take anyone, so they list none.
+    pub fn positions(self) -> &'static [&'static str] {
+        match self {
+            Slot::Qb => &["QB"],
+            Slot::Rb => &["RB"],
+            Slot::Wr => &["WR"],
+            Slot::Te => &["TE"],
+            Slot::Flex => &["RB", "WR", "TE"],
+            Slot::Superflex => &["QB", "RB", "WR", "TE"],
+            Slot::K => &["K"],
+            Slot::Dst => &["DST"],
+            Slot::IdpFlex => &["DL", "LB", "DB"],
+            Slot::Bench | Slot::Ir => &[],
+        }
+    }
+
+    /// Whether players of more than one position can fill the slot
+    pub fn is_flex(self) -> bool {
+        self.positions().len() > 1
+    }
+
+    pub fn accepts(self, position: &str) -> bool {
+        self.positions().contains(&position)
+    }
+}
+
+/// How many of each slot a team's lineup has. Missing fields in a config default to zero.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Slots {
+    pub qb: u32,
+    pub rb: u32,
+    pub wr: u32,
+    pub te: u32,
+    pub flex: u32,
+    pub superflex: u32,
+    pub k: u32,
+    pub dst: u32,
+    pub idp_flex: u32,
+    pub bench: u32,
+    pub ir: u32,
+}
+
+impl Slots {
+    /// 1 QB, 2 RB, 2 WR, 1 TE, 1 FLEX, 1 K, 1 DST, and 6 bench spots
+    pub fn standard() -> Self {
+        Self {
+            qb: 1,
+            rb: 2,
+            wr: 2,
+            te: 1,
+            flex: 1,
+            k: 1,
+            dst: 1,
+            bench: 6,
+            ..Default::default()
+        }
+    }
+
+    pub fn count(&self, slot: Slot) -> u32 {
+        match slot {
+            Slot::Qb => self.qb,
+            Slot::Rb => self.rb,
+            Slot::Wr => self.wr,
+            Slot::Te => self.te,
+            Slot::Flex => self.flex,
+            Slot::Superflex => self.superflex,
+            Slot::K => self.k,
+            Slot::Dst => self.dst,
+            Slot::IdpFlex => self.idp_flex,
+            Slot::Bench => self.bench,
+            Slot::Ir => self.ir,
+        }
+    }
+
+    /// The starting slots a lineup has, with how many of each, from most to least restrictive
+    pub fn starters(&self) -> Vec<(Slot, u32)> {
+        Slot::STARTERS
+            .into_iter()
+            .map(|slot| (slot, self.count(slot)))
+            .filter(|(_, count)| *count > 0)
+            .collect()
+    }
+
+    /// Players a team can roster: starters, bench, and IR
+    pub fn roster_spots(&self) -> u32 {
+        Slot::STARTERS
+            .into_iter()
+            .chain([Slot::Bench, Slot::Ir])
+            .map(|slot| self.count(slot))
+            .sum()
+    }
+
+    /// Roster positions that can start in at least one slot
+    pub fn positions(&self) -> Vec<&'static str> {
+        let mut positions: Vec<_> = self
+            .starters()
+            .into_iter()
+            .flat_map(|(slot, _)| slot.positions().iter().copied())
+            .collect();
+        positions.sort();
+        positions.dedup();
+        positions
+    }
+}
+
+/// A fantasy league: how many teams it has, their lineups, and how points are scored
+#[derive(Copy, Clone, Debug)]
+pub struct League {
+    pub teams: u32,
+    pub slots: Slots,
+    pub scoring: Scoring,
+}
+
+impl Default for League {
+    /// A 12 team PPR league with standard lineups
+    fn default() -> Self {
+        Self {
+            teams: 12,
+            slots: Slots::standard(),
+            scoring: Scoring::ppr(),
+        }
+    }
+}
+
+impl League {
+    /// Loads a league from a TOML or JSON file. Lineup slots go in a `slots` table, and `scoring`
+    /// is either a preset name or a table in the same form as a scoring config:
+    ///
+    /// ```toml
+    /// teams = 10
+    ///
+    /// [slots]
+    /// qb = 1
+    /// rb = 2
+    /// wr = 3
+    /// te = 1
+    /// superflex = 1
+    /// bench = 7
+    ///
+    /// [scoring]
+    /// base = "half-ppr"
+    /// passing_td_points = 6.0
+    /// ```
+    ///
+    /// Anything missing falls back to the default league (12 teams, standard lineups, PPR).
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let value = crate::config::read_value(path)?;
+        Self::from_value(value)
+    }
+
+    pub(crate) fn from_value(value: Value) -> Result<Self> {
+        let Value::Object(mut fields) = value else {
+            return Err(Error::InvalidLeague(
+                "expected a table of league settings".to_string(),
+            ));
+        };
+        let mut league = League::default();
+
+        if let Some(teams) = fields.remove("teams") {
+            league.teams = teams
+                .as_u64()
+                .and_then(|teams| u32::try_from(teams).ok())
+                .filter(|teams| *teams > 0)
+                .ok_or_else(|| {
+                    Error::InvalidLeague(format!(
+                        "teams must be a positive number, found: {}",
+                        teams
+                    ))
+                })?;
+        }
+
+        if let Some(slots) = fields.remove("slots") {
+            league.slots = serde_json::from_value(slots)
+                .map_err(|err| Error::InvalidLeague(format!("slots: {}", err)))?;
+        }
+
+        match fields.remove("scoring") {
+            Some(Value::String(name)) => {
+                let preset = name.parse::<Preset>().map_err(|_| {
+                    Error::InvalidLeague(format!(
+                        "unknown scoring '{}'. Use: ppr, half-ppr, or no-ppr",
+                        name
+                    ))
+                })?;
+                league.scoring = preset.scoring();
+            }
+            Some(scoring) => league.scoring = Scoring::from_value(scoring)?,
+            None => {}
+        }
+
+        if let Some(field) = fields.keys().next() {
+            return Err(Error::InvalidLeague(format!("unknown field '{}'", field)));
+        }
+        if league.slots.starters().is_empty() {
+            return Err(Error::InvalidLeague(
+                "the lineup needs at least one starting slot".to_string(),
+            ));
+        }
+        Ok(league)
+    }
+
+    /// Starters of each slot across the whole league
+    pub fn starters(&self, slot: Slot) -> u32 {
+        self.slots.count(slot) * self.teams
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn invalid(value: Value) -> String {
+        match League::from_value(value) {
+            Err(Error::InvalidLeague(message)) => message,
+            other => panic!("expected an invalid league, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn defaults_to_a_standard_ppr_league() {
+        let league = League::from_value(json!({})).unwrap();
+        assert_eq!(league.teams, 12);
+        assert_eq!(league.slots, Slots::standard());
+        assert_eq!(league.scoring.reception_points, 1.0);
+    }
+
+    #[test]
+    fn reads_teams_slots_and_a_scoring_preset() {
+        let league = League::from_value(json!({
+            "teams": 10,
+            "slots": { "qb": 1, "rb": 2, "superflex": 1, "bench": 5 },
+            "scoring": "half-ppr",
+        }))
+        .unwrap();
+        assert_eq!(league.teams, 10);
+        assert_eq!(
+            league.slots,
+            Slots {
+                qb: 1,
+                rb: 2,
+                superflex: 1,
+                bench: 5,
+                ..Default::default()
+            }
+        );
+        assert_eq!(league.scoring.reception_points, 0.5);
+        assert_eq!(league.starters(Slot::Rb), 20);
+        assert_eq!(league.starters(Slot::Wr), 0);
+        assert_eq!(league.slots.roster_spots(), 9);
+    }
+
+    #[test]
+    fn reads_a_scoring_table() {
+        let league = League::from_value(json!({
+            "scoring": { "base": "no-ppr", "passing_td_points": 6.0 },
+        }))
+        .unwrap();
+        assert_eq!(league.scoring.reception_points, 0.0);
+        assert_eq!(league.scoring.passing_td_points, 6.0);
+    }
+
+    #[test]
+    fn reads_the_example_league() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../leagues/superflex.toml");
+        let league = League::from_file(path).unwrap();
+        assert_eq!(league.teams, 10);
+        assert_eq!(league.slots.idp_flex, 2);
+        assert!(league.scoring.idp_solo_tackle_points > 0.0);
+    }
+
+    #[test]
+    fn rejects_invalid_settings() {
+        assert!(invalid(json!([])).contains("table"));
+        assert!(invalid(json!({ "teams": 0 })).contains("teams"));
+        assert!(invalid(json!({ "teams": "ten" })).contains("teams"));
+        assert!(invalid(json!({ "slots": { "kicker": 1 } })).contains("slots"));
+        assert!(invalid(json!({ "scoring": "standard" })).contains("standard"));
+        assert!(invalid(json!({ "divisions": 2 })).contains("divisions"));
+        assert!(invalid(json!({ "slots": { "bench": 6 } })).contains("starting slot"));
+    }
+
+    #[test]
+    fn orders_starting_slots_from_most_to_least_restrictive() {
+        let slots = Slots {
+            superflex: 1,
+            flex: 1,
+            qb: 1,
+            idp_flex: 1,
+            bench: 3,
+            ..Default::default()
+        };
+        let starters: Vec<_> = slots.starters().into_iter().map(|(slot, _)| slot).collect();
+        assert_eq!(
+            starters,
+            [Slot::Qb, Slot::Flex, Slot::IdpFlex, Slot::Superflex]
+        );
+        assert_eq!(
+            slots.positions(),
+            ["DB", "DL", "LB", "QB", "RB", "TE", "WR"]
+        );
+        assert!(Slot::Superflex.accepts("QB"));
+        assert!(!Slot::Flex.accepts("QB"));
+        assert!(!Slot::Bench.is_flex());
+    }
+}
@@ -2,12 +2,28 @@ use parse_display::{Display, FromStr};
 use polars::prelude::*;
 use std::path::Path;
 
+mod config;
+pub mod consistency;
+pub mod data;
+pub mod defense;
 mod error;
+pub mod exclude;
 pub mod filter;
+pub mod form;
+pub mod league;
+pub mod lineup;
+pub mod lookup;
+mod opportunity;
 pub mod pbp;
+pub mod play;
 pub mod roster;
 pub mod scoring;
-pub use scoring::Scoring;
+pub mod td;
+pub mod usage;
+pub mod vor;
+pub mod xfp;
+pub use data::{DataSource, Dataset};
+pub use scoring::{Preset, Scoring};
 
 type Result<T> = std::result::Result<T, error::Error>;
 
@@ -20,10 +36,23 @@ pub enum Position {
     Te,
     Flex,
     K,
+    Dst,
+    Dl,
+    Lb,
+    Db,
 }
 
-pub fn load_parquet<P: AsRef<Path>>(path: P) -> Result<DataFrame> {
-    let mut file = std::fs::File::open(path)?;
-    let df = ParquetReader::new(&mut file).finish()?;
-    Ok(df)
+/// Lazily scans and concatenates parquet files (e.g. one per season) into a single frame.
+/// Columns whose types drifted between files are cast to a common supertype.
+pub fn scan_parquets<P: AsRef<Path>>(paths: &[P]) -> Result<LazyFrame> {
+    let lfs = paths
+        .iter()
+        .map(|path| LazyFrame::scan_parquet(path, ScanArgsParquet::default()))
+        .collect::<PolarsResult<Vec<_>>>()?;
+    let args = UnionArgs {
+        to_supertypes: true,
+        ..Default::default()
+    };
+    let lf = concat(lfs, args)?;
+    Ok(lf)
 }
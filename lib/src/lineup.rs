@@ -0,0 +1,330 @@
+use crate::{
+    league::{Slot, Slots},
+    roster::RosterDf,
+    scoring::{FantasyStatsDf, Scoring},
+    Result,
+};
+use itertools::Itertools;
+use polars::prelude::*;
+use std::collections::HashMap;
+
+/// A player who can be put in a lineup, with the points used to choose between players
+#[derive(Clone, Debug)]
+pub struct LineupPlayer {
+    pub player_id: String,
+    pub player_name: Option<String>,
+    /// Roster position, or `DST` for team defenses
+    pub position: Option<String>,
+    pub points: f64,
+}
+
+impl LineupPlayer {
+    fn fits(&self, slot: Slot) -> bool {
+        self.position
+            .as_deref()
+            .is_some_and(|position| slot.accepts(position))
+    }
+}
+
+/// Starters by slot (empty when no player is eligible) and everyone else on the bench
+#[derive(Clone, Debug, Default)]
+pub struct Lineup {
+    pub starters: Vec<(Slot, Option<LineupPlayer>)>,
+    pub bench: Vec<LineupPlayer>,
+}
+
+impl Lineup {
+    pub fn points(&self) -> f64 {
+        self.starters
+            .iter()
+            .filter_map(|(_, player)| player.as_ref())
+            .fold(0.0, |total, player| total + player.points)
+    }
+
+    pub fn bench_points(&self) -> f64 {
+        self.bench
+            .iter()
+            .fold(0.0, |total, player| total + player.points)
+    }
+
+    /// The same lineup with each player's points taken from `players` (e.g. actual scores for a
+    /// lineup chosen by projections). Players missing from `players` score 0.
+    pub fn rescore(&self, players: &[LineupPlayer]) -> Lineup {
+        let points: HashMap<&str, f64> = players
+            .iter()
+            .map(|player| (player.player_id.as_str(), player.points))
+            .collect();
+        let rescore = |player: &LineupPlayer| LineupPlayer {
+            points: points
+                .get(player.player_id.as_str())
+                .copied()
+                .unwrap_or(0.0),
+            ..player.clone()
+        };
+        Lineup {
+            starters: self
+                .starters
+                .iter()
+                .map(|(slot, player)| (*slot, player.as_ref().map(rescore)))
+                .collect(),
+            bench: self.bench.iter().map(rescore).collect(),
+        }
+    }
+
+    /// One row per starting slot, then one per bench player (with a `BENCH` slot)
+    pub fn to_df(&self) -> Result<DataFrame> {
+        let rows = self
+            .starters
+            .iter()
+            .map(|(slot, player)| (*slot, player.as_ref()))
+            .chain(self.bench.iter().map(|player| (Slot::Bench, Some(player))));
+
+        let (mut slots, mut ids, mut names, mut positions, mut points) =
+            (Vec::new(), Vec::new(), Vec::new(), Vec::new(), Vec::new());
+        for (slot, player) in rows {
+            slots.push(slot.to_string());
+            ids.push(player.map(|p| p.player_id.clone()));
+            names.push(player.and_then(|p| p.player_name.clone()));
+            positions.push(player.and_then(|p| p.position.clone()));
+            points.push(player.map(|p| p.points));
+        }
+        let df = df!(
+            "slot" => slots,
+            "player_id" => ids,
+            "player_name" => names,
+            "position" => positions,
+            "points" => points,
+        )?;
+        Ok(df)
+    }
+}
+
+/// Fills the lineup with the highest scoring legal set of players.
+///
+/// Slots are filled from the most to the least restrictive (dedicated positions, then FLEX, IDP
+/// flex, and SUPERFLEX), each with the best eligible player left. Each flex slot accepts either
+/// none or all of the positions of the flex slots filled before it, so this maximizes the lineup's
+/// points.
+pub fn optimal_lineup(players: &[LineupPlayer], slots: &Slots) -> Lineup {
+    let mut available: Vec<&LineupPlayer> = players
+        .iter()
+        .sorted_by(|a, b| b.points.total_cmp(&a.points))
+        .collect();
+
+    let mut starters = Vec::new();
+    for (slot, count) in slots.starters() {
+        for _ in 0..count {
+            let pick = available.iter().position(|player| player.fits(slot));
+            let player = pick.map(|i| available.remove(i).clone());
+            if player.is_none() {
+                log::warn!("No eligible player left for a {} slot", slot);
+            }
+            starters.push((slot, player));
+        }
+    }
+
+    Lineup {
+        starters,
+        bench: available.into_iter().cloned().collect(),
+    }
+}
+
+/// Scores the given players (gsis IDs, or team abbreviations for defenses) from the fantasy stats,
+/// e.g. one week's. Positions come from the roster, and players without stats (on bye, injured)
+/// score 0.
+pub fn player_points(
+    fantasy_stats: FantasyStatsDf,
+    roster_df: RosterDf,
+    player_ids: &[String],
+    scoring: Scoring,
+) -> Result<Vec<LineupPlayer>> {
+    let ids = Series::new("ids", player_ids);
+    let scores = fantasy_stats
+        .score_lazy(scoring)?
+        .filter(col("player_id").is_in(lit(ids)))
+        .group_by([col("player_id")])
+        .agg([col("fantasy_points").sum(), col("player_name").first()]);
+    let players = df!("player_id" => player_ids)?.lazy().join(
+        scores,
+        [col("player_id")],
+        [col("player_id")],
+        JoinArgs::new(JoinType::Left),
+    );
+    let df = roster_df.add_positions(players)?.collect()?;
+
+    let ids = df.column("player_id")?.str()?;
+    let names = df.column("player_name")?.str()?;
+    let positions = df.column("position")?.str()?;
+    let points = df.column("fantasy_points")?.f64()?;
+    let mut players = Vec::with_capacity(df.height());
+    for i in 0..df.height() {
+        let Some(player_id) = ids.get(i) else {
+            continue;
+        };
+        let position = positions.get(i).map(String::from);
+        if position.is_none() {
+            log::warn!("No roster position for {}", player_id);
+        }
+        players.push(LineupPlayer {
+            player_id: player_id.to_string(),
+            player_name: names.get(i).map(String::from),
+            position,
+            points: points.get(i).unwrap_or(0.0),
+        });
+    }
+    Ok(players)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn player(player_id: &str, position: &str, points: f64) -> LineupPlayer {
+        LineupPlayer {
+            player_id: player_id.to_string(),
+            player_name: None,
+            position: Some(position.to_string()),
+            points,
+        }
+    }
+
+    fn starter_ids(lineup: &Lineup) -> Vec<(Slot, Option<&str>)> {
+        lineup
+            .starters
+            .iter()
+            .map(|(slot, player)| (*slot, player.as_ref().map(|p| p.player_id.as_str())))
+            .collect()
+    }
+
+    #[test]
+    fn fills_dedicated_slots_before_flex() {
+        let players = [
+            player("QB1", "QB", 20.0),
+            player("RB1", "RB", 15.0),
+            player("RB2", "RB", 10.0),
+            player("WR1", "WR", 12.0),
+        ];
+        let slots = Slots {
+            qb: 1,
+            rb: 1,
+            flex: 1,
+            ..Default::default()
+        };
+        let lineup = optimal_lineup(&players, &slots);
+        assert_eq!(
+            starter_ids(&lineup),
+            [
+                (Slot::Qb, Some("QB1")),
+                (Slot::Rb, Some("RB1")),
+                (Slot::Flex, Some("WR1")),
+            ]
+        );
+        assert_eq!(lineup.points(), 47.0);
+        assert_eq!(lineup.bench_points(), 10.0);
+    }
+
+    #[test]
+    fn superflex_takes_the_best_player_left_after_flex() {
+        let players = [
+            player("QB1", "QB", 25.0),
+            player("QB2", "QB", 18.0),
+            player("RB1", "RB", 15.0),
+            player("RB2", "RB", 10.0),
+            player("WR1", "WR", 12.0),
+        ];
+        let slots = Slots {
+            qb: 1,
+            rb: 1,
+            flex: 1,
+            superflex: 1,
+            ..Default::default()
+        };
+        let lineup = optimal_lineup(&players, &slots);
+        assert_eq!(
+            starter_ids(&lineup),
+            [
+                (Slot::Qb, Some("QB1")),
+                (Slot::Rb, Some("RB1")),
+                (Slot::Flex, Some("WR1")),
+                (Slot::Superflex, Some("QB2")),
+            ]
+        );
+        assert_eq!(lineup.points(), 70.0);
+        assert_eq!(lineup.bench.len(), 1);
+        assert_eq!(lineup.bench[0].player_id, "RB2");
+    }
+
+    #[test]
+    fn leaves_slots_empty_without_eligible_players() {
+        let players = [
+            player("WR1", "WR", 12.0),
+            LineupPlayer {
+                position: None,
+                ..player("X1", "", 30.0)
+            },
+        ];
+        let slots = Slots {
+            te: 1,
+            k: 1,
+            ..Default::default()
+        };
+        let lineup = optimal_lineup(&players, &slots);
+        assert_eq!(starter_ids(&lineup), [(Slot::Te, None), (Slot::K, None)]);
+        assert_eq!(lineup.points(), 0.0);
+        assert_eq!(lineup.bench.len(), 2);
+
+        let empty = optimal_lineup(&[], &slots);
+        assert_eq!(starter_ids(&empty), [(Slot::Te, None), (Slot::K, None)]);
+        assert!(empty.bench.is_empty());
+    }
+
+    #[test]
+    fn starts_the_best_defense() {
+        let players = [player("BUF", "DST", 5.0), player("KC", "DST", 8.0)];
+        let slots = Slots {
+            dst: 1,
+            ..Default::default()
+        };
+        let lineup = optimal_lineup(&players, &slots);
+        assert_eq!(starter_ids(&lineup), [(Slot::Dst, Some("KC"))]);
+        assert_eq!(lineup.bench[0].player_id, "BUF");
+    }
+
+    #[test]
+    fn rescores_a_lineup_picked_by_projections() {
+        let projected = [player("RB1", "RB", 15.0), player("RB2", "RB", 10.0)];
+        let actual = [player("RB2", "RB", 20.0)];
+        let slots = Slots {
+            rb: 1,
+            ..Default::default()
+        };
+        let picked = optimal_lineup(&projected, &slots);
+        assert_eq!(starter_ids(&picked), [(Slot::Rb, Some("RB1"))]);
+
+        // RB1 has no actual score, so it counts as 0
+        let chosen = picked.rescore(&actual);
+        assert_eq!(chosen.points(), 0.0);
+        assert_eq!(chosen.bench_points(), 20.0);
+        assert_eq!(optimal_lineup(&actual, &slots).points(), 20.0);
+    }
+
+    #[test]
+    fn lists_starters_then_bench() {
+        let players = [player("RB1", "RB", 15.0), player("RB2", "RB", 10.0)];
+        let slots = Slots {
+            rb: 1,
+            te: 1,
+            ..Default::default()
+        };
+        let df = optimal_lineup(&players, &slots).to_df().unwrap();
+        let slots: Vec<_> = df
+            .column("slot")
+            .unwrap()
+            .str()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+        assert_eq!(slots, ["RB", "TE", "BENCH"]);
+        assert_eq!(df.column("player_id").unwrap().null_count(), 1);
+    }
+}
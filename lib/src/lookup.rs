@@ -0,0 +1,471 @@
+use crate::{
+    error::Error,
+    pbp::{self, PbpDf},
+    roster::RosterDf,
+    Result,
+};
+use itertools::Itertools;
+use polars::prelude::*;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Scores at or above this are treated as the same name rather than a similar one
+const EXACT: f64 = 0.9;
+
+/// Similarity (Jaro-Winkler) needed for a fuzzy match, e.g. to tolerate typos
+const FUZZY_THRESHOLD: f64 = 0.88;
+
+/// Common short forms of first names, mapped to the forms used on rosters
+static NICKNAMES: [(&str, &str); 24] = [
+    ("pat", "patrick"),
+    ("josh", "joshua"),
+    ("mike", "michael"),
+    ("matt", "matthew"),
+    ("chris", "christopher"),
+    ("nick", "nicholas"),
+    ("rob", "robert"),
+    ("bob", "robert"),
+    ("bobby", "robert"),
+    ("will", "william"),
+    ("bill", "william"),
+    ("jim", "james"),
+    ("jimmy", "james"),
+    ("tom", "thomas"),
+    ("dan", "daniel"),
+    ("joe", "joseph"),
+    ("tony", "anthony"),
+    ("alex", "alexander"),
+    ("ben", "benjamin"),
+    ("sam", "samuel"),
+    ("zach", "zachary"),
+    ("jon", "jonathan"),
+    ("dave", "david"),
+    ("cam", "cameron"),
+];
+
+/// A player that user input can resolve to
+#[derive(Clone, Debug)]
+pub struct Player {
+    pub gsis_id: String,
+    pub full_name: Option<String>,
+    pub position: Option<String>,
+    pub team: Option<String>,
+    /// Abbreviated names from the play-by-play data, e.g. `P.Mahomes`
+    pub pbp_names: Vec<String>,
+    /// Normalized full names, including preferred first names (e.g. `pat mahomes`)
+    names: Vec<String>,
+    last_name: Option<String>,
+}
+
+impl fmt::Display for Player {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = self
+            .full_name
+            .as_deref()
+            .or(self.pbp_names.first().map(String::as_str))
+            .unwrap_or("?");
+        let details = [self.position.as_deref(), self.team.as_deref()]
+            .into_iter()
+            .flatten()
+            .chain([self.gsis_id.as_str()])
+            .join(", ");
+        write!(f, "{} ({})", name, details)
+    }
+}
+
+impl Player {
+    /// How well a normalized query matches this player, from 0 to 1
+    fn score(&self, query: &str) -> f64 {
+        let tokens: Vec<&str> = query.split(' ').collect();
+
+        let abbreviations = self.pbp_names.iter().map(|name| normalize(name));
+        if self.names.iter().any(|name| name == query) || abbreviations.clone().any(|n| n == query)
+        {
+            return 1.0;
+        }
+
+        // "pat mahomes" -> "patrick mahomes"
+        if let Some((first, rest)) = tokens.split_first() {
+            if let Some((_, formal)) = NICKNAMES.iter().find(|(short, _)| short == first) {
+                let expanded = std::iter::once(*formal)
+                    .chain(rest.iter().copied())
+                    .join(" ");
+                if self.names.contains(&expanded) {
+                    return 0.97;
+                }
+            }
+        }
+
+        // "mahomes"
+        if tokens.len() == 1 && self.last_name.as_deref() == Some(query) {
+            return EXACT;
+        }
+
+        let similarity = self
+            .names
+            .iter()
+            .cloned()
+            .chain(abbreviations)
+            .chain(self.last_name.iter().filter(|_| tokens.len() == 1).cloned())
+            .map(|name| strsim::jaro_winkler(query, &name))
+            .fold(0.0, f64::max);
+
+        // Keep fuzzy matches below any exact match, so "mahomes" never loses to a similar name
+        if similarity >= FUZZY_THRESHOLD {
+            similarity * (EXACT - 0.05)
+        } else {
+            0.0
+        }
+    }
+
+    fn matches_qualifier(&self, qualifier: &str) -> bool {
+        let matches = |value: &Option<String>| {
+            value
+                .as_deref()
+                .is_some_and(|value| value.eq_ignore_ascii_case(qualifier))
+        };
+        matches(&self.team) || matches(&self.position)
+    }
+}
+
+/// Resolves loosely typed player names ("mahomes", "Pat Mahomes", "P.Mahomes") to gsis IDs
+#[derive(Clone, Debug, Default)]
+pub struct PlayerLookup {
+    players: Vec<Player>,
+}
+
+impl PlayerLookup {
+    /// Builds the lookup from roster names, adding the abbreviated names each player appears
+    /// under in the play-by-play data
+    pub fn load(roster_df: RosterDf, pbp_df: PbpDf) -> Result<Self> {
+        let mut roster_lf = roster_df.lazy();
+        let schema = roster_lf.schema()?;
+        // Newer rosters have the name a player goes by, e.g. "Pat" for Patrick Mahomes
+        let football_name = if schema.contains("football_name") {
+            col("football_name")
+        } else {
+            lit(NULL).cast(DataType::String).alias("football_name")
+        };
+        let mut sort_cols = vec!["season"];
+        if schema.contains("week") {
+            sort_cols.push("week");
+        }
+
+        // The latest roster entry has the player's current team
+        let roster = roster_lf
+            .filter(col("gsis_id").is_not_null())
+            .sort(sort_cols, SortMultipleOptions::default())
+            .select([
+                col("gsis_id"),
+                col("full_name"),
+                col("first_name"),
+                col("last_name"),
+                football_name,
+                col("position"),
+                col("team"),
+            ])
+            .unique_stable(Some(vec!["gsis_id".to_string()]), UniqueKeepStrategy::Last)
+            .collect()?;
+
+        let pbp_lf = pbp_df.lazy();
+        let pbp_names = concat(
+            pbp::player_column_prefixes()
+                .map(|prefix| {
+                    pbp_lf.clone().select([
+                        col(&format!("{}_player_id", prefix)).alias("player_id"),
+                        col(&format!("{}_player_name", prefix)).alias("player_name"),
+                    ])
+                })
+                .collect::<Vec<_>>(),
+            UnionArgs::default(),
+        )?
+        .drop_nulls(None)
+        .unique(None, UniqueKeepStrategy::Any)
+        .collect()?;
+
+        let mut abbreviations: HashMap<&str, Vec<String>> = HashMap::new();
+        let ids = pbp_names.column("player_id")?.str()?;
+        let names = pbp_names.column("player_name")?.str()?;
+        for (id, name) in ids.into_no_null_iter().zip(names.into_no_null_iter()) {
+            abbreviations.entry(id).or_default().push(name.to_string());
+        }
+
+        let column = |name: &str| roster.column(name).and_then(|series| series.str().cloned());
+        let gsis_ids = column("gsis_id")?;
+        let full_names = column("full_name")?;
+        let first_names = column("first_name")?;
+        let last_names = column("last_name")?;
+        let football_names = column("football_name")?;
+        let positions = column("position")?;
+        let teams = column("team")?;
+
+        let mut players = Vec::with_capacity(roster.height());
+        for i in 0..roster.height() {
+            let Some(gsis_id) = gsis_ids.get(i) else {
+                continue;
+            };
+            let last_name = last_names.get(i).map(normalize);
+            let names = [full_names.get(i).map(normalize)]
+                .into_iter()
+                .chain([first_names.get(i), football_names.get(i)].map(|first| {
+                    let first = first?;
+                    Some(normalize(&format!("{} {}", first, last_name.as_deref()?)))
+                }))
+                .flatten()
+                .unique()
+                .collect();
+
+            let mut pbp_names = abbreviations.remove(gsis_id).unwrap_or_default();
+            pbp_names.sort();
+            players.push(Player {
+                gsis_id: gsis_id.to_string(),
+                full_name: full_names.get(i).map(String::from),
+                position: positions.get(i).map(String::from),
+                team: teams.get(i).map(String::from),
+                pbp_names,
+                names,
+                last_name,
+            });
+        }
+
+        // Players that only appear in the play-by-play data (e.g. missing from the loaded rosters)
+        for (gsis_id, mut pbp_names) in abbreviations {
+            pbp_names.sort();
+            players.push(Player {
+                gsis_id: gsis_id.to_string(),
+                full_name: None,
+                position: None,
+                team: None,
+                pbp_names,
+                names: Vec::new(),
+                last_name: None,
+            });
+        }
+
+        log::debug!("Player lookup has {} players", players.len());
+        Ok(Self { players })
+    }
+
+    /// Whether the abbreviation (e.g. `KC`, in any case) is a team some player is on
+    pub fn is_team(&self, abbreviation: &str) -> bool {
+        self.players.iter().any(|player| {
+            player
+                .team
+                .as_deref()
+                .is_some_and(|team| team.eq_ignore_ascii_case(abbreviation))
+        })
+    }
+
+    /// Players matching the query, best first. A trailing team or position (e.g. "J.Williams DET"
+    /// or "Josh Allen QB") narrows the candidates.
+    pub fn search(&self, query: &str) -> Vec<(f64, &Player)> {
+        if let Some(player) = self.players.iter().find(|p| p.gsis_id == query.trim()) {
+            return vec![(1.0, player)];
+        }
+
+        let mut words: Vec<&str> = query.split_whitespace().collect();
+        let mut qualifiers = Vec::new();
+        while words.len() > 1 {
+            let last = words[words.len() - 1];
+            if !self.players.iter().any(|p| p.matches_qualifier(last)) {
+                break;
+            }
+            qualifiers.push(last);
+            words.pop();
+        }
+
+        let query = normalize(&words.join(" "));
+        self.players
+            .iter()
+            .filter(|player| qualifiers.iter().all(|q| player.matches_qualifier(q)))
+            .map(|player| (player.score(&query), player))
+            .filter(|(score, _)| *score > 0.0)
+            .sorted_by(|(a, _), (b, _)| b.total_cmp(a))
+            .collect()
+    }
+
+    /// Resolves the query to a single player, failing if none or several players match equally well
+    pub fn resolve(&self, query: &str) -> Result<&Player> {
+        let (best, player) = best_match(query, self.search(query))?;
+        if best < EXACT {
+            log::info!("Matched '{}' to {}", query, player);
+        }
+        Ok(player)
+    }
+
+    /// Resolves the query to a single player by gsis ID or exact name, either full or as in the
+    /// play-by-play data (e.g. `Patrick Mahomes` or `P.Mahomes`, in any case). Unlike `resolve`,
+    /// last names, nicknames, and typos match nobody.
+    pub fn resolve_exact(&self, query: &str) -> Result<&Player> {
+        let matches = self
+            .search(query)
+            .into_iter()
+            .filter(|(score, _)| *score >= 1.0)
+            .collect();
+        let (_, player) = best_match(query, matches)?;
+        Ok(player)
+    }
+}
+
+/// The best of the matches for the query, failing if there are none or several tie
+fn best_match<'a>(query: &str, matches: Vec<(f64, &'a Player)>) -> Result<(f64, &'a Player)> {
+    let Some(&(best, player)) = matches.first() else {
+        return Err(Error::PlayerNotFound(query.to_string()));
+    };
+
+    let tied: Vec<&Player> = matches
+        .iter()
+        .take_while(|(score, _)| best - score < 1e-9)
+        .map(|(_, player)| *player)
+        .collect();
+    if tied.len() > 1 {
+        return Err(Error::AmbiguousPlayer {
+            query: query.to_string(),
+            candidates: tied.iter().map(|player| player.to_string()).collect(),
+        });
+    }
+    Ok((best, player))
+}
+
+/// Lowercases and strips punctuation, so "P.Mahomes" becomes "p mahomes" and "D'Andre" "dandre"
+fn normalize(name: &str) -> String {
+    name.to_lowercase()
+        .replace(['\'', '’'], "")
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .join(" ")
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Rosters (gsis ID, full name, position, team) and the names each player appears under in
+    /// the play-by-play data, as passers
+    fn lookup(players: &[(&str, &str, &str, &str)], pbp_names: &[(&str, &str)]) -> PlayerLookup {
+        let field = |i: usize| {
+            players
+                .iter()
+                .map(move |player| [player.0, player.1, player.2, player.3][i])
+        };
+        let (first_names, last_names): (Vec<_>, Vec<_>) =
+            field(1).map(|name| name.split_once(' ').unwrap()).unzip();
+        let roster = df!(
+            "season" => vec![2023; players.len()],
+            "gsis_id" => field(0).collect::<Vec<_>>(),
+            "full_name" => field(1).collect::<Vec<_>>(),
+            "first_name" => first_names,
+            "last_name" => last_names,
+            "position" => field(2).collect::<Vec<_>>(),
+            "team" => field(3).collect::<Vec<_>>(),
+        )
+        .unwrap();
+
+        let (ids, names): (Vec<_>, Vec<_>) = pbp_names.iter().copied().unzip();
+        let columns = pbp::player_column_prefixes()
+            .flat_map(|prefix| {
+                let values = |values: &[&str]| -> Vec<Option<String>> {
+                    values
+                        .iter()
+                        .map(|value| (prefix == "passer").then(|| value.to_string()))
+                        .collect()
+                };
+                [
+                    Series::new(&format!("{}_player_id", prefix), values(&ids)),
+                    Series::new(&format!("{}_player_name", prefix), values(&names)),
+                ]
+            })
+            .collect::<Vec<_>>();
+        let pbp = DataFrame::new(columns).unwrap();
+
+        PlayerLookup::load(RosterDf::new(roster.lazy()), PbpDf::new(pbp.lazy())).unwrap()
+    }
+
+    /// Patrick Mahomes and Travis Kelce (KC), and two Josh Allens (QB BUF and LB JAX)
+    pub(crate) fn players() -> PlayerLookup {
+        lookup(
+            &[
+                ("00-1", "Patrick Mahomes", "QB", "KC"),
+                ("00-2", "Josh Allen", "QB", "BUF"),
+                ("00-3", "Josh Allen", "LB", "JAX"),
+                ("00-4", "Travis Kelce", "TE", "KC"),
+            ],
+            &[
+                ("00-1", "P.Mahomes"),
+                ("00-2", "J.Allen"),
+                ("00-3", "J.Allen"),
+                ("00-4", "T.Kelce"),
+            ],
+        )
+    }
+
+    fn resolved(lookup: &PlayerLookup, query: &str) -> String {
+        lookup.resolve(query).unwrap().gsis_id.clone()
+    }
+
+    #[test]
+    fn normalizes_names() {
+        assert_eq!(normalize("P.Mahomes"), "p mahomes");
+        assert_eq!(normalize("D'Andre Swift"), "dandre swift");
+        assert_eq!(normalize("  Amon-Ra St. Brown "), "amon ra st brown");
+    }
+
+    #[test]
+    fn resolves_loosely_typed_names() {
+        let lookup = players();
+        for query in [
+            "00-1",
+            "Patrick Mahomes",
+            "patrick mahomes",
+            "P.Mahomes",
+            "mahomes",
+            "pat mahomes",
+            "mahomse",
+        ] {
+            assert_eq!(resolved(&lookup, query), "00-1", "{}", query);
+        }
+        assert_eq!(resolved(&lookup, "kelce"), "00-4");
+    }
+
+    #[test]
+    fn ranks_exact_matches_above_nicknames_and_typos() {
+        let lookup = players();
+        let mahomes = &lookup.players[0];
+        let exact = mahomes.score("patrick mahomes");
+        let nickname = mahomes.score("pat mahomes");
+        let last_name = mahomes.score("mahomes");
+        let typo = mahomes.score("mahomse");
+        assert_eq!(exact, 1.0);
+        assert!(exact > nickname && nickname > last_name && last_name > typo && typo > 0.0);
+        assert_eq!(mahomes.score("kelce"), 0.0);
+    }
+
+    #[test]
+    fn qualifiers_pick_between_players_with_the_same_name() {
+        let lookup = players();
+        match lookup.resolve("Josh Allen") {
+            Err(Error::AmbiguousPlayer { candidates, .. }) => assert_eq!(candidates.len(), 2),
+            other => panic!("expected an ambiguous player, got {:?}", other),
+        }
+        assert!(matches!(
+            lookup.resolve("J.Allen"),
+            Err(Error::AmbiguousPlayer { .. })
+        ));
+        assert_eq!(resolved(&lookup, "Josh Allen QB"), "00-2");
+        assert_eq!(resolved(&lookup, "josh allen jax"), "00-3");
+        assert_eq!(resolved(&lookup, "J.Allen BUF"), "00-2");
+    }
+
+    #[test]
+    fn fails_without_a_match() {
+        let lookup = players();
+        assert!(matches!(
+            lookup.resolve("zzzz"),
+            Err(Error::PlayerNotFound(query)) if query == "zzzz"
+        ));
+        assert!(matches!(
+            lookup.resolve("Kelce QB"),
+            Err(Error::PlayerNotFound(_))
+        ));
+    }
+}
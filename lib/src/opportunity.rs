@@ -0,0 +1,101 @@
+use polars::prelude::*;
+
+/// Every target with its context, excluding two-point conversions
+pub(crate) fn targets(plays: LazyFrame) -> LazyFrame {
+    plays
+        .filter(
+            col("receiver_player_id")
+                .is_not_null()
+                .and(col("two_point_attempt").fill_null(lit(0.0)).eq(lit(0.0))),
+        )
+        .select([
+            col("game_id"),
+            col("season"),
+            col("week"),
+            col("posteam").alias("team"),
+            col("defteam").alias("opponent"),
+            col("receiver_player_id").alias("player_id"),
+            col("receiver_player_name").alias("player_name"),
+            field_position(),
+            col("goal_to_go"),
+            depth(),
+            // Missing values would never match when joining on the groups
+            col("pass_location").fill_null(lit("unknown")),
+            col("cp"),
+            col("air_yards"),
+            col("xyac_mean_yardage"),
+            col("complete_pass").fill_null(lit(0.0)).alias("receptions"),
+            col("receiving_yards").fill_null(lit(0.0)),
+            touchdown("receiver_player_id"),
+        ])
+}
+
+/// Every designed run with its context, excluding two-point conversions. QB kneels and scrambles
+/// aren't carries the offense chose to give, and would otherwise count as runs up the middle.
+pub(crate) fn carries(plays: LazyFrame) -> LazyFrame {
+    let not = |flag: &str| col(flag).fill_null(lit(0.0)).eq(lit(0.0));
+    plays
+        .filter(
+            col("rusher_player_id")
+                .is_not_null()
+                .and(not("two_point_attempt"))
+                .and(not("qb_kneel"))
+                .and(not("qb_scramble")),
+        )
+        .select([
+            col("game_id"),
+            col("season"),
+            col("week"),
+            col("posteam").alias("team"),
+            col("defteam").alias("opponent"),
+            col("rusher_player_id").alias("player_id"),
+            col("rusher_player_name").alias("player_name"),
+            field_position(),
+            col("goal_to_go"),
+            // Runs up the middle have no gap
+            col("run_gap").fill_null(lit("middle")),
+            col("rushing_yards").fill_null(lit(0.0)),
+            touchdown("rusher_player_id"),
+        ])
+}
+
+/// Distance from the end zone, in bands that matter for touchdown odds
+fn field_position() -> Expr {
+    let yardline = col("yardline_100").fill_null(lit(100));
+    when(yardline.clone().lt_eq(lit(5)))
+        .then(lit(5))
+        .when(yardline.clone().lt_eq(lit(10)))
+        .then(lit(10))
+        .when(yardline.clone().lt_eq(lit(20)))
+        .then(lit(20))
+        .when(yardline.clone().lt_eq(lit(40)))
+        .then(lit(40))
+        .when(yardline.lt_eq(lit(60)))
+        .then(lit(60))
+        .otherwise(lit(100))
+        .alias("field_position")
+}
+
+/// Pass depth: behind the line, short, intermediate, or deep (-1 when unknown)
+fn depth() -> Expr {
+    let air_yards = col("air_yards");
+    when(air_yards.clone().is_null())
+        .then(lit(-1))
+        .when(air_yards.clone().lt(lit(0.0)))
+        .then(lit(0))
+        .when(air_yards.clone().lt(lit(10.0)))
+        .then(lit(10))
+        .when(air_yards.lt(lit(20.0)))
+        .then(lit(20))
+        .otherwise(lit(99))
+        .alias("depth")
+}
+
+/// Whether the play's touchdown was scored by the player in the column (not e.g. on a fumble return)
+fn touchdown(player_col: &str) -> Expr {
+    col("td_player_id")
+        .eq(col(player_col))
+        .fill_null(lit(false))
+        .cast(DataType::Float64)
+        .alias("touchdowns")
+}
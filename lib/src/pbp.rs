@@ -1,33 +1,95 @@
 use crate::{
+    data::{DataSource, Dataset},
     error::Error,
     roster::RosterDf,
     scoring::{self, FantasyStatsDf},
-    Result,
+    usage, Result,
 };
 use derive_deref::Deref;
 use polars::{prelude::*, sql::SQLContext};
 
+/// Play-by-play data as a lazy query. Filters and the columns used by the stat queries are
+/// pushed down into the parquet scan, and nothing is read until a result is collected.
 #[derive(Clone, Deref)]
-pub struct PbpDf(DataFrame);
+pub struct PbpDf(LazyFrame);
 
 impl PbpDf {
-    pub fn new(df: DataFrame) -> Self {
-        PbpDf(df)
+    pub fn new(lf: LazyFrame) -> Self {
+        PbpDf(lf)
     }
 
-    pub fn load(year: u16) -> Result<Self> {
-        let df = crate::load_parquet(format!("data/pbp_{}.parquet", year))?;
-        Ok(PbpDf(df))
+    pub fn load(source: &DataSource, year: u16) -> Result<Self> {
+        Self::load_seasons(source, &[year])
+    }
+
+    /// Scans several seasons as one frame, which remain distinguishable by the `season` column.
+    /// Only the columns in `columns` are read.
+    pub fn load_seasons(source: &DataSource, years: &[u16]) -> Result<Self> {
+        let lf = Self::load_seasons_with_all_columns(source, years)?.0;
+        Ok(PbpDf(lf.select(
+            columns().map(|name| col(&name)).collect::<Vec<_>>(),
+        )))
+    }
+
+    /// Scans several seasons with every play-by-play column, e.g. for ad-hoc SQL
+    pub fn load_seasons_with_all_columns(source: &DataSource, years: &[u16]) -> Result<Self> {
+        let paths = years
+            .iter()
+            .map(|&year| source.require(Dataset::Pbp, Some(year)))
+            .collect::<Result<Vec<_>>>()?;
+        let lf = crate::scan_parquets(&paths)?;
+        Ok(PbpDf(lf))
     }
 
     pub fn filter(self, filter: Expr) -> Result<Self> {
-        let df = self.0.lazy().filter(filter).collect()?;
-        Ok(PbpDf(df))
+        Ok(PbpDf(self.0.filter(filter)))
+    }
+
+    /// Keeps every play of the games with at least one play matching the filter, e.g. a player's
+    /// games rather than only their plays, so team totals such as target share stay complete
+    pub fn games_with(self, filter: Expr) -> Result<Self> {
+        let game_ids = self
+            .0
+            .clone()
+            .filter(filter)
+            .select([col("game_id")])
+            .unique(None, UniqueKeepStrategy::Any);
+        let lf = self.0.join(
+            game_ids,
+            [col("game_id")],
+            [col("game_id")],
+            JoinArgs::new(JoinType::Inner),
+        );
+        Ok(PbpDf(lf))
+    }
+
+    /// Reads the plays into memory, so later queries skip the parquet scan
+    pub fn cache(self) -> Result<Self> {
+        Ok(PbpDf(self.collect()?.lazy()))
+    }
+
+    pub fn lazy(self) -> LazyFrame {
+        self.0
+    }
+
+    pub fn collect(self) -> Result<DataFrame> {
+        let df = self.0.collect()?;
+        log::debug!("Collected {} plays", df.height());
+        Ok(df)
     }
 
     pub fn merge_roster(&self, roster_df: RosterDf) -> Result<DataFrame> {
         let join_args = JoinArgs::new(JoinType::Inner).with_coalesce(JoinCoalesce::CoalesceColumns);
-        let merged_df = self.join(&roster_df, ["player_id"], ["gsis_id"], join_args)?;
+        let merged_df = self
+            .0
+            .clone()
+            .join(
+                roster_df.lazy(),
+                [col("player_id")],
+                [col("gsis_id")],
+                join_args,
+            )
+            .collect()?;
 
         log::debug!("{} total players with fantasy points", merged_df.height());
         Ok(merged_df)
@@ -36,36 +98,90 @@ impl PbpDf {
     /// Takes a play-by-play dataframe and returns a new dataframe containing fantasy stats by player
     pub fn fantasy_stats(self) -> Result<FantasyStatsDf> {
         log::trace!("pbp::fantasy_game_stats");
-        let df = self.0;
+        let lf = self.0;
+
+        let idp_lf = scoring::idp_stats(lf.clone())?;
+        let points_allowed_lf = scoring::dst_points_allowed(lf.clone())?;
+        let usage_lf = usage::usage_stats(lf.clone())?;
+
+        // Every credited team played either the home or away side, so the other side is its opponent
+        let games_lf = lf
+            .clone()
+            .select([col("game_id"), col("home_team"), col("away_team")])
+            .unique(None, UniqueKeepStrategy::Any);
 
         let mut ctx = SQLContext::new();
-        ctx.register("plays", df.lazy());
-
-        let passing_df = ctx.execute(scoring::PASSING_QUERY)?.collect()?;
-        log::debug!("{} passers with fantasy points", passing_df.height());
-        let receiving_df = ctx.execute(scoring::RECEIVING_QUERY)?.collect()?;
-        log::debug!("{} receivers with fantasy points", receiving_df.height());
-        let rushing_df = ctx.execute(scoring::RUSHING_QUERY)?.collect()?;
-        log::debug!("{} rushers with fantasy points", rushing_df.height());
-        let fumbling_df = ctx.execute(scoring::FUMBLING_QUERY)?.collect()?;
-        log::debug!("{} fumblers with fantasy points", fumbling_df.height());
-        let kicking_df = ctx.execute(scoring::KICKING_QUERY)?.collect()?;
-        log::debug!("{} kickers with fantasy points", kicking_df.height());
-        let returning_df = ctx.execute(scoring::RETURNING_QUERY)?.collect()?;
-        log::debug!("{} returners with fantasy points", returning_df.height());
-
-        // Merge the DataFrames on team, player_id, and player_name
-        let join_cols = ["game_id", "team", "player_id", "player_name"];
+        ctx.register("plays", lf);
+
+        let passing_lf = ctx.execute(scoring::PASSING_QUERY)?;
+        let receiving_lf = ctx.execute(scoring::RECEIVING_QUERY)?;
+        let rushing_lf = ctx.execute(scoring::RUSHING_QUERY)?;
+        let fumbling_lf = ctx.execute(scoring::FUMBLING_QUERY)?;
+        let fumble_recovery_lf = ctx.execute(scoring::FUMBLE_RECOVERY_QUERY)?;
+        let kicking_lf = ctx.execute(scoring::KICKING_QUERY)?;
+        let returning_lf = ctx.execute(scoring::RETURNING_QUERY)?;
+        let dst_lf = ctx.execute(scoring::DST_QUERY)?;
+        let dst_returning_lf = ctx.execute(scoring::DST_RETURNING_QUERY)?;
+
+        // Merge the DataFrames on game, team, player_id, and player_name
+        let join_cols = [
+            col("game_id"),
+            col("season"),
+            col("week"),
+            col("team"),
+            col("player_id"),
+            col("player_name"),
+        ];
         let join_args = JoinArgs::new(JoinType::Full).with_coalesce(JoinCoalesce::CoalesceColumns);
-        let merged_df = passing_df
-            .join(&receiving_df, join_cols, join_cols, join_args.clone())?
-            .join(&rushing_df, join_cols, join_cols, join_args.clone())?
-            .join(&passing_df, join_cols, join_cols, join_args.clone())?
-            .join(&kicking_df, join_cols, join_cols, join_args.clone())?
-            .join(&returning_df, join_cols, join_cols, join_args.clone())?
-            .join(&fumbling_df, join_cols, join_cols, join_args.clone())?;
-        log::debug!("{} total players with fantasy points", merged_df.height());
-        Ok(FantasyStatsDf::new(merged_df))
+        let merged_lf = [
+            receiving_lf,
+            rushing_lf,
+            kicking_lf,
+            returning_lf,
+            fumbling_lf,
+            fumble_recovery_lf,
+            dst_lf,
+            points_allowed_lf,
+            dst_returning_lf,
+            idp_lf,
+            usage_lf,
+        ]
+        .into_iter()
+        .fold(passing_lf, |merged, stats| {
+            merged.join(
+                stats,
+                join_cols.clone(),
+                join_cols.clone(),
+                join_args.clone(),
+            )
+        });
+
+        let opponent = when(col("team").eq(col("home_team")))
+            .then(col("away_team"))
+            .otherwise(col("home_team"))
+            .alias("opponent");
+        let keys = [
+            "game_id",
+            "season",
+            "week",
+            "team",
+            "player_id",
+            "player_name",
+        ];
+        let stats_lf = merged_lf
+            .join(
+                games_lf,
+                [col("game_id")],
+                [col("game_id")],
+                JoinArgs::new(JoinType::Left),
+            )
+            .select([
+                cols(&keys[..4]),
+                opponent,
+                cols(&keys[4..]),
+                all().exclude(keys.into_iter().chain(["home_team", "away_team"])),
+            ]);
+        Ok(FantasyStatsDf::new(stats_lf))
     }
 }
 
@@ -92,7 +208,96 @@ pub fn assert_single_game_id(df: &DataFrame) -> Result<()> {
     }
 }
 
-#[derive(Clone)]
+/// Columns read by the stat queries, filters, and `explain`, besides the player columns of
+/// `player_column_prefixes` and the team columns of `scoring::IDP_CREDITS`
+const COLUMNS: [&str; 46] = [
+    "play_id",
+    "game_id",
+    "season",
+    "week",
+    "home_team",
+    "away_team",
+    "home_score",
+    "away_score",
+    "posteam",
+    "defteam",
+    "qtr",
+    "time",
+    "desc",
+    "play_type",
+    "yardline_100",
+    "goal_to_go",
+    "yards_gained",
+    "qb_kneel",
+    "qb_scramble",
+    "pass_location",
+    "air_yards",
+    "run_gap",
+    "cp",
+    "xyac_mean_yardage",
+    "complete_pass",
+    "passing_yards",
+    "receiving_yards",
+    "rushing_yards",
+    "touchdown",
+    "pass_touchdown",
+    "rush_touchdown",
+    "return_touchdown",
+    "td_team",
+    "td_player_id",
+    "td_player_name",
+    "two_point_attempt",
+    "two_point_conv_result",
+    "extra_point_attempt",
+    "extra_point_result",
+    "field_goal_result",
+    "kick_distance",
+    "punt_blocked",
+    "interception",
+    "fumble_lost",
+    "safety",
+    "sack",
+];
+
+/// Every column `PbpDf::load_seasons` reads
+pub(crate) fn columns() -> impl Iterator<Item = String> {
+    let player_cols = player_column_prefixes().flat_map(|prefix| {
+        [
+            format!("{}_player_id", prefix),
+            format!("{}_player_name", prefix),
+        ]
+    });
+    let idp_team_cols = scoring::IDP_CREDITS
+        .iter()
+        .filter_map(|(_, team_col, _)| team_col.map(String::from));
+    COLUMNS
+        .into_iter()
+        .map(String::from)
+        .chain(player_cols)
+        .chain(idp_team_cols)
+}
+
+/// Prefixes of the `<prefix>_player_id` and `<prefix>_player_name` columns that credit a player
+/// with a play, including the defensive players credited for IDP stats
+pub(crate) fn player_column_prefixes() -> impl Iterator<Item = &'static str> {
+    [
+        "passer",
+        "receiver",
+        "rusher",
+        "fumbled_1",
+        "kicker",
+        "lateral_kickoff_returner",
+        "lateral_punt_returner",
+        "kickoff_returner",
+        "punt_returner",
+        "fumble_recovery_1",
+        "fumble_recovery_2",
+    ]
+    .into_iter()
+    .chain(scoring::IDP_CREDITS.iter().map(|(prefix, _, _)| *prefix))
+}
+
+#[derive(Clone, Default)]
 pub struct PbpFilter {
     filter_expr: Option<Expr>,
 }
@@ -107,6 +312,15 @@ impl PbpFilter {
         self.extend_filter(expr)
     }
 
+    // Adds a filter for plays involving the team on either side of the ball, e.g. for its defense
+    // or its opponents
+    pub fn involving_team(mut self, team_name: &str) -> Self {
+        let expr = col("posteam")
+            .eq(lit(team_name))
+            .or(col("defteam").eq(lit(team_name)));
+        self.extend_filter(expr)
+    }
+
     // Adds a filter for the game
     pub fn game(mut self, game_id: &str) -> Self {
         let expr = col("game_id").eq(lit(game_id));
@@ -115,21 +329,8 @@ impl PbpFilter {
 
     // Adds a filter for the player, which matches against multiple columns
     pub fn player_id(mut self, player_id: &str) -> Self {
-        let player_columns = vec![
-            "passer_player_id",
-            "receiver_player_id",
-            "rusher_player_id",
-            "fumbled_1_player_id",
-            "kicker_player_id",
-            "lateral_kickoff_returner_player_id",
-            "lateral_punt_returner_player_id",
-            "kickoff_returner_player_id",
-            "punt_returner_player_id",
-        ];
-
-        let expr = player_columns
-            .into_iter()
-            .map(|col_name| col(col_name).eq(lit(player_id)))
+        let expr = player_column_prefixes()
+            .map(|prefix| col(&format!("{}_player_id", prefix)).eq(lit(player_id)))
             .reduce(|acc, expr| acc.or(expr))
             .unwrap();
 
@@ -138,21 +339,8 @@ impl PbpFilter {
 
     // Adds a filter for the player, which matches against multiple columns
     pub fn player_name(mut self, player_name: &str) -> Self {
-        let player_columns = vec![
-            "passer_player_name",
-            "receiver_player_name",
-            "rusher_player_name",
-            "fumbled_1_player_name",
-            "kicker_player_name",
-            "lateral_kickoff_returner_player_name",
-            "lateral_punt_returner_player_name",
-            "kickoff_returner_player_name",
-            "punt_returner_player_name",
-        ];
-
-        let expr = player_columns
-            .into_iter()
-            .map(|col_name| col(col_name).eq(lit(player_name)))
+        let expr = player_column_prefixes()
+            .map(|prefix| col(&format!("{}_player_name", prefix)).eq(lit(player_name)))
             .reduce(|acc, expr| acc.or(expr))
             .unwrap();
 
@@ -184,3 +372,102 @@ impl PbpFilter {
         self.filter_expr.unwrap_or_else(|| lit(true))
     }
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    /// Flags that are 0 rather than null on plays they don't apply to
+    const FLAGS: [&str; 15] = [
+        "goal_to_go",
+        "qb_kneel",
+        "qb_scramble",
+        "complete_pass",
+        "touchdown",
+        "pass_touchdown",
+        "rush_touchdown",
+        "return_touchdown",
+        "two_point_attempt",
+        "extra_point_attempt",
+        "punt_blocked",
+        "interception",
+        "fumble_lost",
+        "safety",
+        "sack",
+    ];
+
+    /// Plays of BUF at KC in week 1 of 2023 with every column `PbpDf::load_seasons` reads. Each
+    /// play sets some columns, and the rest are null, or 0 for flags. KC has the ball unless a
+    /// play says otherwise, and plays are numbered in order.
+    pub(crate) fn plays(plays: &[Value]) -> PbpDf {
+        let defaults = json!({
+            "game_id": "2023_01_BUF_KC",
+            "season": 2023,
+            "week": 1,
+            "home_team": "KC",
+            "away_team": "BUF",
+            "posteam": "KC",
+            "defteam": "BUF",
+        });
+        let columns = columns()
+            .map(|name| {
+                let values = plays
+                    .iter()
+                    .enumerate()
+                    .map(|(i, play)| match (name.as_str(), play.get(&name)) {
+                        (_, Some(value)) => value.clone(),
+                        ("play_id", None) => json!(i + 1),
+                        // The other team defends, if a team has the ball
+                        ("defteam", None) => match play.get("posteam") {
+                            Some(Value::String(team)) if team == "BUF" => json!("KC"),
+                            Some(Value::String(_)) | None => json!("BUF"),
+                            Some(_) => Value::Null,
+                        },
+                        (name, None) if FLAGS.contains(&name) => json!(0),
+                        _ => defaults.get(&name).cloned().unwrap_or(Value::Null),
+                    })
+                    .collect::<Vec<_>>();
+                column(&name, &values)
+            })
+            .collect::<Vec<_>>();
+        PbpDf::new(DataFrame::new(columns).unwrap().lazy())
+    }
+
+    /// A column with the type it has in the nflverse data
+    fn column(name: &str, values: &[Value]) -> Series {
+        let strings = [
+            "game_id",
+            "home_team",
+            "away_team",
+            "posteam",
+            "defteam",
+            "time",
+            "desc",
+            "play_type",
+            "pass_location",
+            "run_gap",
+            "td_team",
+            "two_point_conv_result",
+            "extra_point_result",
+            "field_goal_result",
+        ];
+        let is_string = strings.contains(&name)
+            || (name.ends_with("_id") && name != "play_id")
+            || name.ends_with("_name")
+            || name.ends_with("_team");
+        if is_string {
+            let values: Vec<_> = values.iter().map(|v| v.as_str()).collect();
+            Series::new(name, values)
+        } else if ["season", "week", "yardline_100", "yards_gained"].contains(&name) {
+            let values: Vec<_> = values
+                .iter()
+                .map(|v| v.as_i64().map(|v| v as i32))
+                .collect();
+            Series::new(name, values)
+        } else {
+            let values: Vec<_> = values.iter().map(|v| v.as_f64()).collect();
+            Series::new(name, values)
+        }
+    }
+}
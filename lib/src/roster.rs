@@ -1,33 +1,104 @@
-use crate::{Position, Result};
+use crate::{
+    data::{DataSource, Dataset},
+    league::Slot,
+    Position, Result,
+};
 use derive_deref::Deref;
 use polars::prelude::*;
 
+/// Weekly rosters as a lazy query, collected only when joined into a result
 #[derive(Clone, Deref)]
-pub struct RosterDf(DataFrame);
+pub struct RosterDf(LazyFrame);
 
 impl RosterDf {
-    pub fn new(df: DataFrame) -> Self {
-        RosterDf(df)
+    pub fn new(lf: LazyFrame) -> Self {
+        RosterDf(lf)
     }
 
-    pub fn load(year: u16) -> Result<Self> {
-        let df = crate::load_parquet(format!("data/rosters_{}.parquet", year))?;
-        Ok(RosterDf(df))
+    pub fn load(source: &DataSource, year: u16) -> Result<Self> {
+        Self::load_seasons(source, &[year])
+    }
+
+    /// Scans several seasons as one frame, which remain distinguishable by the `season` column
+    pub fn load_seasons(source: &DataSource, years: &[u16]) -> Result<Self> {
+        let paths = years
+            .iter()
+            .map(|&year| source.require(Dataset::Rosters, Some(year)))
+            .collect::<Result<Vec<_>>>()?;
+        let lf = crate::scan_parquets(&paths)?;
+        Ok(RosterDf(lf))
     }
 
     pub fn filter(self, filter: Expr) -> Result<Self> {
-        let df = self.0.lazy().filter(filter).collect()?;
-        Ok(RosterDf(df))
+        Ok(RosterDf(self.0.filter(filter)))
     }
 
     pub fn unique_players(self) -> Result<Self> {
         let expr = col("gsis_id").is_first_distinct();
-        let df = self.0.lazy().filter(expr).collect()?;
-        Ok(RosterDf(df))
+        Ok(RosterDf(self.0.filter(expr)))
+    }
+
+    /// Adds each player's roster `position` to a frame keyed by `player_id`, such as scores. Team
+    /// defenses aren't on the roster, but their player_id is their team, so they get `DST`.
+    pub fn add_positions(self, lf: LazyFrame) -> Result<LazyFrame> {
+        let teams = self
+            .0
+            .clone()
+            .select([col("team").unique().alias("defense")])
+            .with_column(lit(true).alias("is_defense"));
+        let positions = self
+            .unique_players()?
+            .0
+            .select([col("gsis_id"), col("position")]);
+        let lf = lf
+            .join(
+                positions,
+                [col("player_id")],
+                [col("gsis_id")],
+                JoinArgs::new(JoinType::Left),
+            )
+            .join(
+                teams,
+                [col("player_id")],
+                [col("defense")],
+                JoinArgs::new(JoinType::Left),
+            )
+            .with_column(
+                when(col("is_defense").fill_null(lit(false)))
+                    .then(lit("DST"))
+                    .otherwise(col("position"))
+                    .alias("position"),
+            )
+            .select([all().exclude(["is_defense"])]);
+        Ok(lf)
+    }
+
+    /// Drops the given players (by gsis ID), e.g. those resolved from an exclusion list
+    pub fn exclude(self, player_ids: &[String]) -> Result<Self> {
+        if player_ids.is_empty() {
+            return Ok(self);
+        }
+        let excluded = Series::new("excluded", player_ids);
+        self.filter(col("gsis_id").is_in(lit(excluded)).not())
+    }
+
+    /// Reads the rosters into memory, so later queries skip the parquet scan
+    pub fn cache(self) -> Result<Self> {
+        Ok(RosterDf(self.collect()?.lazy()))
+    }
+
+    pub fn lazy(self) -> LazyFrame {
+        self.0
+    }
+
+    pub fn collect(self) -> Result<DataFrame> {
+        let df = self.0.collect()?;
+        log::debug!("Collected {} roster entries", df.height());
+        Ok(df)
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct RosterFilter {
     filter_expr: Option<Expr>,
 }
@@ -62,11 +133,9 @@ impl RosterFilter {
     pub fn position(mut self, position: Position) -> Self {
         let expr = match position {
             Position::Flex => {
-                // Flex should match WR, TE, or RB
-                col("position")
-                    .eq(lit(Position::Wr.to_string().to_uppercase()))
-                    .or(col("position").eq(lit(Position::Te.to_string().to_uppercase())))
-                    .or(col("position").eq(lit(Position::Rb.to_string().to_uppercase())))
+                // Flex matches any position that can fill a FLEX slot
+                let positions = Series::new("positions", Slot::Flex.positions());
+                col("position").is_in(lit(positions))
             }
             _ => {
                 // Default case: match the specific position
@@ -1,9 +1,35 @@
 use crate::roster::RosterDf;
-use crate::Result;
+use crate::{error::Error, Result};
 use derive_deref::Deref;
+use parse_display::{Display, FromStr};
 use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
 
-#[derive(Debug, Clone, Copy)]
+/// Built-in scoring systems that a scoring config can use as its `base`
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Display, FromStr)]
+#[display(style = "kebab-case")]
+pub enum Preset {
+    Ppr,
+    HalfPpr,
+    NoPpr,
+}
+
+impl Preset {
+    pub fn scoring(self) -> Scoring {
+        match self {
+            Preset::Ppr => Scoring::ppr(),
+            Preset::HalfPpr => Scoring::half_ppr(),
+            Preset::NoPpr => Scoring::no_ppr(),
+        }
+    }
+}
+
+/// Fantasy scoring rules. Fields missing from a config file default to zero
+/// unless the config sets a `base` preset to inherit from.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct Scoring {
     pub passing_yd_per_point: f64,
     pub passing_300yd_bonus: f64,
@@ -29,11 +55,47 @@ pub struct Scoring {
 
     pub interception_points: f64,
     pub fumble_lost_points: f64,
-    pub two_point_conversion_points: f64,
+    pub two_pt_pass_points: f64,
+    pub two_pt_rec_points: f64,
+    pub two_pt_rush_points: f64,
     pub fg_made_points: f64,
     pub fg_made_40yd_bonus: f64,
     pub fg_made_50yd_bonus: f64,
     pub pat_made_points: f64,
+
+    pub dst_sack_points: f64,
+    pub dst_interception_points: f64,
+    pub dst_fumble_recovery_points: f64,
+    pub dst_safety_points: f64,
+    pub dst_td_points: f64,
+    pub dst_blocked_kick_points: f64,
+
+    pub points_allowed_0_points: f64,
+    pub points_allowed_1_6_points: f64,
+    pub points_allowed_7_13_points: f64,
+    pub points_allowed_14_20_points: f64,
+    pub points_allowed_21_27_points: f64,
+    pub points_allowed_28_34_points: f64,
+    pub points_allowed_35plus_points: f64,
+
+    pub yards_allowed_0_99_points: f64,
+    pub yards_allowed_100_199_points: f64,
+    pub yards_allowed_200_299_points: f64,
+    pub yards_allowed_300_349_points: f64,
+    pub yards_allowed_350_399_points: f64,
+    pub yards_allowed_400_449_points: f64,
+    pub yards_allowed_450_499_points: f64,
+    pub yards_allowed_500plus_points: f64,
+
+    pub idp_solo_tackle_points: f64,
+    pub idp_assist_tackle_points: f64,
+    pub idp_sack_points: f64,
+    pub idp_half_sack_points: f64,
+    pub idp_tfl_points: f64,
+    pub idp_qb_hit_points: f64,
+    pub idp_pass_defended_points: f64,
+    pub idp_forced_fumble_points: f64,
+    pub idp_interception_points: f64,
 }
 
 impl Scoring {
@@ -48,7 +110,9 @@ impl Scoring {
             receiving_yd_per_point: 10.0,
             receiving_td_points: 6.0,
             fumble_lost_points: -2.0,
-            two_point_conversion_points: 2.0,
+            two_pt_pass_points: 2.0,
+            two_pt_rec_points: 2.0,
+            two_pt_rush_points: 2.0,
             fg_made_points: 3.0,
             pat_made_points: 1.0,
             passing_300yd_bonus: 0.0,
@@ -64,6 +128,36 @@ impl Scoring {
             return_td_points: 6.0,
             fg_made_40yd_bonus: 0.0,
             fg_made_50yd_bonus: 0.0,
+            dst_sack_points: 1.0,
+            dst_interception_points: 2.0,
+            dst_fumble_recovery_points: 2.0,
+            dst_safety_points: 2.0,
+            dst_td_points: 6.0,
+            dst_blocked_kick_points: 2.0,
+            points_allowed_0_points: 10.0,
+            points_allowed_1_6_points: 7.0,
+            points_allowed_7_13_points: 4.0,
+            points_allowed_14_20_points: 1.0,
+            points_allowed_21_27_points: 0.0,
+            points_allowed_28_34_points: -1.0,
+            points_allowed_35plus_points: -4.0,
+            yards_allowed_0_99_points: 0.0,
+            yards_allowed_100_199_points: 0.0,
+            yards_allowed_200_299_points: 0.0,
+            yards_allowed_300_349_points: 0.0,
+            yards_allowed_350_399_points: 0.0,
+            yards_allowed_400_449_points: 0.0,
+            yards_allowed_450_499_points: 0.0,
+            yards_allowed_500plus_points: 0.0,
+            idp_solo_tackle_points: 0.0,
+            idp_assist_tackle_points: 0.0,
+            idp_sack_points: 0.0,
+            idp_half_sack_points: 0.0,
+            idp_tfl_points: 0.0,
+            idp_qb_hit_points: 0.0,
+            idp_pass_defended_points: 0.0,
+            idp_forced_fumble_points: 0.0,
+            idp_interception_points: 0.0,
         }
     }
 
@@ -78,69 +172,176 @@ impl Scoring {
         scoring.reception_points = 0.0;
         scoring
     }
+
+    /// Loads scoring from a TOML or JSON file, e.g.:
+    ///
+    /// ```toml
+    /// base = "half-ppr"
+    /// passing_td_points = 6.0
+    /// fg_made_50yd_bonus = 2.0
+    /// ```
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let value = crate::config::read_value(path)?;
+        Self::from_value(value)
+    }
+
+    /// Loads scoring from the contents of a TOML config, like `from_file`
+    pub fn from_toml(contents: &str) -> Result<Self> {
+        Self::from_value(toml::from_str(contents)?)
+    }
+
+    /// Builds scoring from a config value, applying overrides on top of the optional `base` preset
+    pub(crate) fn from_value(value: Value) -> Result<Self> {
+        let Value::Object(mut overrides) = value else {
+            return Err(Error::InvalidScoring(
+                "expected a table of scoring fields".to_string(),
+            ));
+        };
+
+        let base = match overrides.remove("base") {
+            Some(Value::String(name)) => name
+                .parse::<Preset>()
+                .map_err(|_| {
+                    Error::InvalidScoring(format!(
+                        "unknown base '{}'. Use: ppr, half-ppr, or no-ppr",
+                        name
+                    ))
+                })?
+                .scoring(),
+            Some(other) => {
+                return Err(Error::InvalidScoring(format!(
+                    "base must be a preset name, found: {}",
+                    other
+                )))
+            }
+            None => Scoring::default(),
+        };
+
+        let Value::Object(mut merged) = serde_json::to_value(base)? else {
+            unreachable!("Scoring always serializes to an object");
+        };
+        merged.extend(overrides);
+
+        let scoring: Scoring = serde_json::from_value(Value::Object(merged))
+            .map_err(|err| Error::InvalidScoring(err.to_string()))?;
+        scoring.validate()?;
+        Ok(scoring)
+    }
+
+    /// Checks that the scoring values can produce meaningful points
+    pub fn validate(&self) -> Result<()> {
+        let Value::Object(fields) = serde_json::to_value(self)? else {
+            unreachable!("Scoring always serializes to an object");
+        };
+        for (name, value) in fields {
+            let value = value.as_f64().unwrap_or(f64::NAN);
+            if !value.is_finite() {
+                return Err(Error::InvalidScoring(format!(
+                    "{} must be a finite number",
+                    name
+                )));
+            }
+            if name.ends_with("_yd_per_point") && value <= 0.0 {
+                return Err(Error::InvalidScoring(format!(
+                    "{} must be greater than zero, found {} (set it or choose a base preset)",
+                    name, value
+                )));
+            }
+        }
+        Ok(())
+    }
 }
 
 pub(crate) static PASSING_QUERY: &str = r#"
     SELECT 
         game_id,
+        season,
+        week,
         posteam as team,
         passer_player_id as player_id,
         passer_player_name as player_name,
         SUM(passing_yards) as passing_yards,
         SUM(pass_touchdown) as pass_touchdowns,
         SUM(interception) as interceptions,
+        SUM(CASE WHEN two_point_conv_result = 'success' THEN 1 ELSE 0 END) as two_pt_pass,
         SUM(CASE WHEN passing_yards > 50 THEN pass_touchdown ELSE 0 END) as passing_50yd_td
     FROM plays
     WHERE passer_player_name IS NOT NULL
-    GROUP BY game_id, posteam, passer_player_id, passer_player_name
+    GROUP BY game_id, season, week, posteam, passer_player_id, passer_player_name
 "#;
 
 pub(crate) static RECEIVING_QUERY: &str = r#"
     SELECT
         game_id,
+        season,
+        week,
         posteam as team,
         receiver_player_id as player_id,
         receiver_player_name as player_name,
         SUM(complete_pass) as receptions,
         SUM(receiving_yards) as receiving_yards,
-        SUM(pass_touchdown) as receiving_touchdowns,
-        SUM(CASE WHEN two_point_conv_result = 'success' THEN 1 ELSE 0 END) as two_pt_conv_made,
-        SUM(CASE WHEN receiving_yards > 50 THEN pass_touchdown ELSE 0 END) as receiving_50yd_td
+        SUM(CASE WHEN td_player_id = fumble_recovery_1_player_id OR td_player_id = fumble_recovery_2_player_id THEN 0 ELSE pass_touchdown END) as receiving_touchdowns,
+        SUM(CASE WHEN two_point_conv_result = 'success' THEN 1 ELSE 0 END) as two_pt_rec,
+        SUM(CASE WHEN receiving_yards > 50 AND td_player_id = receiver_player_id THEN pass_touchdown ELSE 0 END) as receiving_50yd_td
     FROM plays
     WHERE receiver_player_name IS NOT NULL
-    GROUP BY game_id, posteam, receiver_player_id, receiver_player_name
+    GROUP BY game_id, season, week, posteam, receiver_player_id, receiver_player_name
 "#;
 
 pub(crate) static RUSHING_QUERY: &str = r#"
     SELECT
         game_id,
+        season,
+        week,
         posteam as team,
         rusher_player_id as player_id,
         rusher_player_name as player_name,
         SUM(rushing_yards) as rushing_yards,
-        SUM(rush_touchdown) as rush_touchdowns,
-        SUM(CASE WHEN two_point_conv_result = 'success' THEN 1 ELSE 0 END) as two_pt_conv_made,
-        SUM(CASE WHEN rushing_yards > 50 THEN rush_touchdown ELSE 0 END) as rushing_50yd_td
+        SUM(CASE WHEN td_player_id = fumble_recovery_1_player_id OR td_player_id = fumble_recovery_2_player_id THEN 0 ELSE rush_touchdown END) as rush_touchdowns,
+        SUM(CASE WHEN two_point_conv_result = 'success' THEN 1 ELSE 0 END) as two_pt_rush,
+        SUM(CASE WHEN rushing_yards > 50 AND td_player_id = rusher_player_id THEN rush_touchdown ELSE 0 END) as rushing_50yd_td
     FROM plays
     WHERE rusher_player_name IS NOT NULL
-    GROUP BY game_id, posteam, rusher_player_id, rusher_player_name
+    GROUP BY game_id, season, week, posteam, rusher_player_id, rusher_player_name
 "#;
 
 pub(crate) static FUMBLING_QUERY: &str = r#"
     SELECT
         game_id,
+        season,
+        week,
         posteam as team,
         fumbled_1_player_id as player_id,
         fumbled_1_player_name as player_name,
         SUM(fumble_lost) as fumbles_lost
     FROM plays
     WHERE fumbled_1_player_name IS NOT NULL
-    GROUP BY game_id, posteam, fumbled_1_player_id, fumbled_1_player_name
+    GROUP BY game_id, season, week, posteam, fumbled_1_player_id, fumbled_1_player_name
+"#;
+
+/// Offensive TDs scored by recovering a fumble in the end zone. These are credited to the
+/// recovering player rather than counted as a rushing or receiving TD for the ball carrier.
+pub(crate) static FUMBLE_RECOVERY_QUERY: &str = r#"
+    SELECT
+        game_id,
+        season,
+        week,
+        posteam as team,
+        td_player_id as player_id,
+        td_player_name as player_name,
+        SUM(touchdown) as fumble_recovery_tds
+    FROM plays
+    WHERE touchdown = 1.0
+        AND td_team = posteam
+        AND (td_player_id = fumble_recovery_1_player_id OR td_player_id = fumble_recovery_2_player_id)
+    GROUP BY game_id, season, week, posteam, td_player_id, td_player_name
 "#;
 
 pub(crate) static KICKING_QUERY: &str = r#"
     SELECT
         game_id,
+        season,
+        week,
         posteam as team,
         kicker_player_id as player_id,
         kicker_player_name as player_name,
@@ -150,12 +351,16 @@ pub(crate) static KICKING_QUERY: &str = r#"
         SUM(CASE WHEN kick_distance >= 50 AND field_goal_result = 'made' THEN 1 ELSE 0 END) as fg_50plus_made
     FROM plays
     WHERE play_type != 'kickoff' AND kicker_player_name IS NOT NULL
-    GROUP BY game_id, posteam, kicker_player_id, kicker_player_name
+    GROUP BY game_id, season, week, posteam, kicker_player_id, kicker_player_name
 "#;
 
+/// Kickoff and punt return TDs for the returner. Interception, fumble, and blocked kick returns
+/// are left to the team defense, since the return columns only name kick returners.
 pub(crate) static RETURNING_QUERY: &str = r#"
     SELECT
         game_id,
+        season,
+        week,
         team,
         player_id,
         player_name,
@@ -163,6 +368,8 @@ pub(crate) static RETURNING_QUERY: &str = r#"
     FROM (
         SELECT 
             game_id,
+            season,
+            week,
             posteam as team,
             COALESCE(
                 lateral_kickoff_returner_player_id, 
@@ -178,37 +385,282 @@ pub(crate) static RETURNING_QUERY: &str = r#"
             ) as player_name,
             return_touchdown
         FROM plays
-        WHERE return_touchdown = 1.0
+        WHERE return_touchdown = 1.0 AND play_type IN ('kickoff', 'punt')
     ) as coalesced_players
-    GROUP BY game_id, team, player_id, player_name
+    GROUP BY game_id, season, week, team, player_id, player_name
+"#;
+
+/// Team defense / special teams stats, keyed by the defending team. Points allowed is the
+/// opponent's final score, and yards allowed only counts offensive plays.
+pub(crate) static DST_QUERY: &str = r#"
+    SELECT
+        game_id,
+        season,
+        week,
+        defteam as team,
+        defteam as player_id,
+        defteam as player_name,
+        SUM(sack) as def_sacks,
+        SUM(interception) as def_interceptions,
+        SUM(CASE WHEN play_type != 'punt' THEN fumble_lost ELSE 0 END) as def_fumble_recoveries,
+        SUM(safety) as def_safeties,
+        SUM(CASE WHEN td_team = defteam AND NOT (play_type IN ('kickoff', 'punt') AND return_touchdown = 1.0) THEN touchdown ELSE 0 END) as def_touchdowns,
+        SUM(CASE WHEN field_goal_result = 'blocked' OR extra_point_result = 'blocked' OR punt_blocked = 1.0 THEN 1 ELSE 0 END) as def_blocked_kicks,
+        SUM(CASE WHEN play_type IN ('pass', 'run') THEN yards_gained ELSE 0 END) as yards_allowed
+    FROM plays
+    WHERE defteam IS NOT NULL
+    GROUP BY game_id, season, week, defteam
+"#;
+
+/// Kickoff and punt return TDs for team special teams. Keyed by `td_team` since the
+/// returning team is `posteam` on kickoffs but `defteam` on punts.
+pub(crate) static DST_RETURNING_QUERY: &str = r#"
+    SELECT
+        game_id,
+        season,
+        week,
+        td_team as team,
+        td_team as player_id,
+        td_team as player_name,
+        SUM(return_touchdown) as st_touchdowns
+    FROM plays
+    WHERE return_touchdown = 1.0 AND td_team IS NOT NULL AND play_type IN ('kickoff', 'punt')
+    GROUP BY game_id, season, week, td_team
 "#;
 
+/// Points each team defense allowed: the opponent's final score, less the points the defense wasn't
+/// on the field for. Those are the opponent's touchdowns on returns, fumbles, and interceptions
+/// (and the conversions after them), and its safeties.
+pub(crate) fn dst_points_allowed(plays: LazyFrame) -> Result<LazyFrame> {
+    let flag = |name: &str| col(name).fill_null(lit(0.0)).eq(lit(1.0));
+    let offense_td = col("td_team")
+        .eq(col("posteam"))
+        .and(col("play_type").is_in(lit(Series::new("scrimmage", ["pass", "run"]))));
+    let return_td_team = when(flag("touchdown").and(offense_td.not()))
+        .then(col("td_team"))
+        .otherwise(lit(NULL).cast(DataType::String));
+    // Conversions follow their touchdown, once the plays in between that can't score are dropped
+    let converting = col("posteam").eq(col("return_td_team").shift(lit(1)).over([col("game_id")]));
+    let conversion_points = when(col("extra_point_result").eq(lit("good")))
+        .then(lit(1.0))
+        .when(col("two_point_conv_result").eq(lit("success")))
+        .then(lit(2.0))
+        .otherwise(lit(0.0));
+    let unearned = plays
+        .clone()
+        .filter(
+            flag("touchdown")
+                .or(flag("safety"))
+                .or(flag("extra_point_attempt"))
+                .or(flag("two_point_attempt")),
+        )
+        .sort(["game_id", "play_id"], SortMultipleOptions::default())
+        .with_column(return_td_team.alias("return_td_team"))
+        .select([
+            col("game_id"),
+            when(col("return_td_team").is_not_null())
+                .then(col("return_td_team"))
+                .when(flag("safety"))
+                .then(col("defteam"))
+                .otherwise(col("posteam"))
+                .alias("scoring_team"),
+            when(col("return_td_team").is_not_null())
+                .then(lit(6.0))
+                .when(flag("safety"))
+                .then(lit(2.0))
+                .when(converting.fill_null(lit(false)))
+                .then(conversion_points)
+                .otherwise(lit(0.0))
+                .alias("unearned_points"),
+        ])
+        .group_by([col("game_id"), col("scoring_team")])
+        .agg([col("unearned_points").sum()]);
+
+    let games = plays
+        .filter(col("defteam").is_not_null())
+        .group_by([col("game_id"), col("season"), col("week"), col("defteam")])
+        .agg([
+            when(col("defteam").eq(col("home_team")))
+                .then(col("away_team"))
+                .otherwise(col("home_team"))
+                .first()
+                .alias("opponent"),
+            when(col("defteam").eq(col("home_team")))
+                .then(col("away_score"))
+                .otherwise(col("home_score"))
+                .max()
+                .alias("opponent_score"),
+        ]);
+    let lf = games
+        .join(
+            unearned,
+            [col("game_id"), col("opponent")],
+            [col("game_id"), col("scoring_team")],
+            JoinArgs::new(JoinType::Left),
+        )
+        .select([
+            col("game_id"),
+            col("season"),
+            col("week"),
+            col("defteam").alias("team"),
+            col("defteam").alias("player_id"),
+            col("defteam").alias("player_name"),
+            (col("opponent_score") - col("unearned_points").fill_null(lit(0.0)))
+                .alias("points_allowed"),
+        ]);
+    Ok(lf)
+}
+
+static IDP_STATS: [&str; 9] = [
+    "idp_solo_tackles",
+    "idp_assist_tackles",
+    "idp_sacks",
+    "idp_half_sacks",
+    "idp_tfl",
+    "idp_qb_hits",
+    "idp_pass_defended",
+    "idp_forced_fumbles",
+    "idp_interceptions",
+];
+
+/// Individual defensive credits as (column prefix, team column, stat).
+/// Prefixes without a team column are credited to the `defteam`.
+#[rustfmt::skip]
+pub(crate) static IDP_CREDITS: [(&str, Option<&str>, &str); 20] = [
+    ("solo_tackle_1", Some("solo_tackle_1_team"), "idp_solo_tackles"),
+    ("solo_tackle_2", Some("solo_tackle_2_team"), "idp_solo_tackles"),
+    ("assist_tackle_1", Some("assist_tackle_1_team"), "idp_assist_tackles"),
+    ("assist_tackle_2", Some("assist_tackle_2_team"), "idp_assist_tackles"),
+    ("assist_tackle_3", Some("assist_tackle_3_team"), "idp_assist_tackles"),
+    ("assist_tackle_4", Some("assist_tackle_4_team"), "idp_assist_tackles"),
+    ("tackle_with_assist_1", Some("tackle_with_assist_1_team"), "idp_assist_tackles"),
+    ("tackle_with_assist_2", Some("tackle_with_assist_2_team"), "idp_assist_tackles"),
+    ("sack", None, "idp_sacks"),
+    ("half_sack_1", None, "idp_half_sacks"),
+    ("half_sack_2", None, "idp_half_sacks"),
+    ("tackle_for_loss_1", None, "idp_tfl"),
+    ("tackle_for_loss_2", None, "idp_tfl"),
+    ("qb_hit_1", None, "idp_qb_hits"),
+    ("qb_hit_2", None, "idp_qb_hits"),
+    ("pass_defense_1", None, "idp_pass_defended"),
+    ("pass_defense_2", None, "idp_pass_defended"),
+    ("forced_fumble_player_1", Some("forced_fumble_player_1_team"), "idp_forced_fumbles"),
+    ("forced_fumble_player_2", Some("forced_fumble_player_2_team"), "idp_forced_fumbles"),
+    ("interception", None, "idp_interceptions"),
+];
+
+/// Individual defensive player stats. Each play can credit several defenders, so every
+/// `IDP_CREDITS` column is stacked into one long frame of (player, stat) credits before summing.
+pub(crate) fn idp_stats(plays: LazyFrame) -> Result<LazyFrame> {
+    let credits = IDP_CREDITS
+        .iter()
+        .map(|&(prefix, team_col, stat)| {
+            let id_col = format!("{}_player_id", prefix);
+            let name_col = format!("{}_player_name", prefix);
+            let team = col(team_col.unwrap_or("defteam"));
+
+            let mut columns = vec![
+                col("game_id"),
+                col("season"),
+                col("week"),
+                team.alias("team"),
+                col(&id_col).alias("player_id"),
+                col(&name_col).alias("player_name"),
+            ];
+            columns.extend(IDP_STATS.iter().map(|&name| {
+                let value = if name == stat { 1.0 } else { 0.0 };
+                lit(value).alias(name)
+            }));
+
+            plays
+                .clone()
+                .filter(col(&id_col).is_not_null())
+                .select(columns)
+        })
+        .collect::<Vec<_>>();
+
+    let df = concat(credits, UnionArgs::default())?
+        .group_by([
+            col("game_id"),
+            col("season"),
+            col("week"),
+            col("team"),
+            col("player_id"),
+            col("player_name"),
+        ])
+        .agg([cols(IDP_STATS).sum()]);
+    Ok(df)
+}
+
+/// Per player-game fantasy stats as a lazy query over the play-by-play scan
 #[derive(Clone, Deref)]
-pub struct FantasyStatsDf(DataFrame);
+pub struct FantasyStatsDf(LazyFrame);
 
 impl FantasyStatsDf {
-    pub(crate) fn new(df: DataFrame) -> Self {
-        Self(df)
+    pub fn new(lf: LazyFrame) -> Self {
+        Self(lf)
     }
 
     pub fn filter(self, filter: Expr) -> Result<Self> {
-        let df = self.0.lazy().filter(filter).collect()?;
-        Ok(Self(df))
+        Ok(Self(self.0.filter(filter)))
+    }
+
+    /// Keeps only team defense / special teams rows, which use the team as their player_id
+    pub fn defenses(self) -> Result<Self> {
+        self.filter(col("player_id").eq(col("team")))
     }
 
-    pub fn merge_roster(&self, roster_df: RosterDf) -> Result<FantasyStatsDf> {
+    /// Drops the given players (by gsis ID, or team abbreviation for defenses), e.g. those
+    /// resolved from an exclusion list
+    pub fn exclude(self, player_ids: &[String]) -> Result<Self> {
+        if player_ids.is_empty() {
+            return Ok(self);
+        }
+        let excluded = Series::new("excluded", player_ids);
+        self.filter(col("player_id").is_in(lit(excluded)).not())
+    }
+
+    pub fn merge_roster(self, roster_df: RosterDf) -> Result<FantasyStatsDf> {
+        // Stats already know the season, week, and team of each game, so keep those over the roster's
+        let mut stats_lf = self.0;
+        let mut roster_lf = roster_df.lazy();
+        let existing = stats_lf.schema()?;
+        let roster_cols: Vec<_> = roster_lf
+            .schema()?
+            .iter_names()
+            .filter(|name| !existing.contains(name))
+            .map(|name| col(name))
+            .collect();
+
         let join_args = JoinArgs::new(JoinType::Inner).with_coalesce(JoinCoalesce::CoalesceColumns);
-        let merged_df = self.join(&roster_df, ["player_id"], ["gsis_id"], join_args)?;
+        let merged_lf = stats_lf.join(
+            roster_lf.select(roster_cols),
+            [col("player_id")],
+            [col("gsis_id")],
+            join_args,
+        );
+        Ok(Self(merged_lf))
+    }
 
-        log::debug!("{} total players with fantasy points", merged_df.height());
-        Ok(Self(merged_df))
+    pub fn lazy(self) -> LazyFrame {
+        self.0
+    }
+
+    pub fn collect(self) -> Result<DataFrame> {
+        let df = self.0.collect()?;
+        log::debug!("{} player-games with fantasy stats", df.height());
+        Ok(df)
+    }
+
+    /// Computes the stats into memory, so they can be scored repeatedly without rereading plays
+    pub fn cache(self) -> Result<Self> {
+        Ok(Self(self.collect()?.lazy()))
     }
 
     /// Takes player fantasy stats dataframe and calculates fantasy score
-    fn score_lazy(self, scoring: Scoring) -> Result<LazyFrame> {
+    pub(crate) fn score_lazy(self, scoring: Scoring) -> Result<LazyFrame> {
         let fantasy_df = self
             .0
-            .lazy()
             .with_column(scoring_cols(scoring).alias("fantasy_points"));
         Ok(fantasy_df)
     }
@@ -240,6 +692,101 @@ impl FantasyStatsDf {
         Ok(df)
     }
 
+    /// Season totals for each player, with the change in points per game from their previous season
+    pub fn score_by_player_season(self, scoring: Scoring) -> Result<DataFrame> {
+        let lf = self.score_lazy(scoring)?;
+        let seasons = lf
+            .group_by(&[col("player_id"), col("season")])
+            .agg([
+                col("fantasy_points").sum(),
+                col("game_id").n_unique().alias("games"),
+                cols(["player_name", "team"]).last(),
+            ])
+            .with_column(
+                (col("fantasy_points") / col("games").cast(DataType::Float64))
+                    .alias("points_per_game"),
+            );
+        // Players who missed a season have nothing to compare the next one to
+        let previous = seasons.clone().select([
+            col("player_id"),
+            (col("season") + lit(1)).alias("next_season"),
+            col("points_per_game").alias("previous_points_per_game"),
+        ]);
+        let df = seasons
+            .join(
+                previous,
+                [col("player_id"), col("season")],
+                [col("player_id"), col("next_season")],
+                JoinArgs::new(JoinType::Left),
+            )
+            .with_column(
+                (col("points_per_game") - col("previous_points_per_game")).alias("yoy_change"),
+            )
+            .select([all().exclude(["previous_points_per_game"])])
+            .sort(
+                ["season", "fantasy_points"],
+                SortMultipleOptions::default().with_order_descending_multi([false, true]),
+            )
+            .collect()?;
+        Ok(df)
+    }
+
+    /// Totals for each player against each opponent, with how their points per game against that
+    /// opponent compare to their average over all games (`vs_avg`)
+    pub fn score_by_player_opponent(self, scoring: Scoring) -> Result<DataFrame> {
+        let lf = self.score_lazy(scoring)?;
+        let player_avg = col("fantasy_points").sum().over([col("player_id")])
+            / col("games")
+                .sum()
+                .over([col("player_id")])
+                .cast(DataType::Float64);
+        let df = lf
+            .group_by(&[col("player_id"), col("opponent")])
+            .agg([
+                col("fantasy_points").sum(),
+                col("game_id").n_unique().alias("games"),
+                cols(["player_name", "team"]).last(),
+                col("season").min().alias("first_season"),
+                col("season").max().alias("last_season"),
+            ])
+            .with_column(
+                (col("fantasy_points") / col("games").cast(DataType::Float64))
+                    .alias("points_per_game"),
+            )
+            .with_column((col("points_per_game") - player_avg).alias("vs_avg"))
+            .sort(
+                ["fantasy_points"],
+                SortMultipleOptions::default().with_order_descending(true),
+            )
+            .collect()?;
+        Ok(df)
+    }
+
+    /// Itemizes each player-game's score into one `*_pts` column per scoring component
+    pub fn score_breakdown(self, scoring: Scoring) -> Result<DataFrame> {
+        let mut columns = vec![cols([
+            "game_id",
+            "season",
+            "week",
+            "team",
+            "opponent",
+            "player_id",
+            "player_name",
+        ])];
+        columns.extend(scoring_components(scoring));
+        columns.push(scoring_cols(scoring).alias("fantasy_points"));
+
+        let df = self
+            .0
+            .select(columns)
+            .sort(
+                ["fantasy_points"],
+                SortMultipleOptions::default().with_order_descending(true),
+            )
+            .collect()?;
+        Ok(df)
+    }
+
     // ScoreBy::Game => &["game_id", "week", "team", "fantasy_points"],
     pub fn score_by_game(self, scoring: Scoring) -> Result<DataFrame> {
         let lf = self.score_lazy(scoring)?;
@@ -255,53 +802,512 @@ impl FantasyStatsDf {
     }
 }
 
-fn scoring_cols(scoring: Scoring) -> Expr {
-    col("passing_yards").fill_null(lit(0.0)) / lit(scoring.passing_yd_per_point)
-        + col("pass_touchdowns").fill_null(lit(0.0)) * lit(scoring.passing_td_points)
-        + col("rushing_yards").fill_null(lit(0.0)) / lit(scoring.rushing_yd_per_point)
-        + col("rush_touchdowns").fill_null(lit(0.0)) * lit(scoring.rushing_td_points)
-        + col("receptions").fill_null(lit(0.0)) * lit(scoring.reception_points)
-        + col("receiving_yards").fill_null(lit(0.0)) / lit(scoring.receiving_yd_per_point)
-        + col("receiving_touchdowns").fill_null(lit(0.0)) * lit(scoring.receiving_td_points)
-        + col("interceptions").fill_null(lit(0.0)) * lit(scoring.interception_points)
-        + col("fumbles_lost").fill_null(lit(0.0)) * lit(scoring.fumble_lost_points)
-        + col("fg_made").fill_null(lit(0.0)) * lit(scoring.fg_made_points)
-        + col("pat_made").fill_null(lit(0.0)) * lit(scoring.pat_made_points)
-        + col("td_returns").fill_null(lit(0.0)) * lit(scoring.return_td_points)
-        + col("two_pt_conv_made").fill_null(lit(0.0)) * lit(scoring.two_point_conversion_points)
-        // TODO: Figure out how to count fumble recovery TDs
+pub(crate) fn scoring_cols(scoring: Scoring) -> Expr {
+    scoring_components(scoring)
+        .into_iter()
+        .reduce(|acc, expr| acc + expr)
+        .unwrap()
+}
 
+/// Each part of the fantasy score as its own `*_pts` column, which sum to `fantasy_points`
+fn scoring_components(scoring: Scoring) -> Vec<Expr> {
+    let stat = |name: &str| col(name).fill_null(lit(0.0));
+    let bonus = |name: &str, threshold: f64, points: f64| {
+        when(col(name).gt(lit(threshold)))
+            .then(lit(points))
+            .otherwise(lit(0.0))
+    };
 
+    vec![
+        (stat("passing_yards") / lit(scoring.passing_yd_per_point)).alias("passing_yds_pts"),
+        (stat("pass_touchdowns") * lit(scoring.passing_td_points)).alias("passing_td_pts"),
+        (stat("rushing_yards") / lit(scoring.rushing_yd_per_point)).alias("rushing_yds_pts"),
+        (stat("rush_touchdowns") * lit(scoring.rushing_td_points)).alias("rushing_td_pts"),
+        (stat("receptions") * lit(scoring.reception_points)).alias("reception_pts"),
+        (stat("receiving_yards") / lit(scoring.receiving_yd_per_point)).alias("receiving_yds_pts"),
+        (stat("receiving_touchdowns") * lit(scoring.receiving_td_points)).alias("receiving_td_pts"),
+        (stat("interceptions") * lit(scoring.interception_points)
+            + stat("fumbles_lost") * lit(scoring.fumble_lost_points))
+        .alias("turnover_pts"),
+        (stat("fg_made") * lit(scoring.fg_made_points)
+            + stat("pat_made") * lit(scoring.pat_made_points))
+        .alias("kicking_pts"),
+        (stat("td_returns") * lit(scoring.return_td_points)).alias("return_td_pts"),
+        (stat("two_pt_pass") * lit(scoring.two_pt_pass_points)
+            + stat("two_pt_rec") * lit(scoring.two_pt_rec_points)
+            + stat("two_pt_rush") * lit(scoring.two_pt_rush_points))
+        .alias("two_pt_pts"),
+        (stat("fumble_recovery_tds") * lit(scoring.fumble_recovery_td_points))
+            .alias("fumble_recovery_td_pts"),
         // All the code below here is because my brother
         // couldn't just pick standard scoring when starting
         // a game with a bunch of family that hasn't played FF before.
         // A true jerk move. There, I said it.
+        (stat("passing_50yd_td") * lit(scoring.passing_td_50yd_bonus)
+            + bonus("passing_yards", 300.0, scoring.passing_300yd_bonus)
+            + bonus("passing_yards", 400.0, scoring.passing_400yd_bonus))
+        .alias("passing_bonus_pts"),
+        (stat("rushing_50yd_td") * lit(scoring.rushing_td_50yd_bonus)
+            + bonus("rushing_yards", 100.0, scoring.rushing_100yd_bonus)
+            + bonus("rushing_yards", 200.0, scoring.rushing_200yd_bonus))
+        .alias("rushing_bonus_pts"),
+        (stat("receiving_50yd_td") * lit(scoring.receiving_td_50yd_bonus)
+            + bonus("receiving_yards", 100.0, scoring.receiving_100yd_bonus)
+            + bonus("receiving_yards", 200.0, scoring.receiving_200yd_bonus))
+        .alias("receiving_bonus_pts"),
+        (stat("fg_40plus_made") * lit(scoring.fg_made_40yd_bonus)
+            + stat("fg_50plus_made") * lit(scoring.fg_made_50yd_bonus))
+        .alias("kicking_bonus_pts"),
+        // Team defense / special teams
+        (stat("def_sacks") * lit(scoring.dst_sack_points)
+            + stat("def_interceptions") * lit(scoring.dst_interception_points)
+            + stat("def_fumble_recoveries") * lit(scoring.dst_fumble_recovery_points)
+            + stat("def_safeties") * lit(scoring.dst_safety_points)
+            + stat("def_touchdowns") * lit(scoring.dst_td_points)
+            + stat("st_touchdowns") * lit(scoring.dst_td_points)
+            + stat("def_blocked_kicks") * lit(scoring.dst_blocked_kick_points))
+        .alias("dst_pts"),
+        points_allowed_col(scoring).alias("dst_points_allowed_pts"),
+        yards_allowed_col(scoring).alias("dst_yards_allowed_pts"),
+        // Individual defensive players
+        (stat("idp_solo_tackles") * lit(scoring.idp_solo_tackle_points)
+            + stat("idp_assist_tackles") * lit(scoring.idp_assist_tackle_points)
+            + stat("idp_sacks") * lit(scoring.idp_sack_points)
+            + stat("idp_half_sacks") * lit(scoring.idp_half_sack_points)
+            + stat("idp_tfl") * lit(scoring.idp_tfl_points)
+            + stat("idp_qb_hits") * lit(scoring.idp_qb_hit_points)
+            + stat("idp_pass_defended") * lit(scoring.idp_pass_defended_points)
+            + stat("idp_forced_fumbles") * lit(scoring.idp_forced_fumble_points)
+            + stat("idp_interceptions") * lit(scoring.idp_interception_points))
+        .alias("idp_pts"),
+    ]
+}
 
-        // Passing bonuses
-        + col("passing_50yd_td").fill_null(lit(0.0)) * lit(scoring.passing_td_50yd_bonus)
-        + when(col("passing_yards").gt(lit(300.0)))
-            .then(lit(scoring.passing_300yd_bonus))
-            .otherwise(lit(0.0))
-        + when(col("passing_yards").gt(lit(400.0)))
-            .then(lit(scoring.passing_400yd_bonus))
-            .otherwise(lit(0.0))
-        // Rushing bonuses
-        + col("rushing_50yd_td").fill_null(lit(0.0)) * lit(scoring.rushing_td_50yd_bonus)
-        + when(col("rushing_yards").gt(lit(100.0)))
-            .then(lit(scoring.rushing_100yd_bonus))
-            .otherwise(lit(0.0))
-        + when(col("rushing_yards").gt(lit(200.0)))
-            .then(lit(scoring.rushing_200yd_bonus))
-            .otherwise(lit(0.0))
-        // Receiving bonuses
-        + col("receiving_50yd_td").fill_null(lit(0.0)) * lit(scoring.receiving_td_50yd_bonus)
-        + when(col("receiving_yards").gt(lit(100.0)))
-            .then(lit(scoring.receiving_100yd_bonus))
-            .otherwise(lit(0.0))
-        + when(col("receiving_yards").gt(lit(200.0)))
-            .then(lit(scoring.receiving_200yd_bonus))
-            .otherwise(lit(0.0))
-        // Kicking bonuses
-        + col("fg_40plus_made").fill_null(lit(0.0)) * lit(scoring.fg_made_40yd_bonus)
-        + col("fg_50plus_made").fill_null(lit(0.0)) * lit(scoring.fg_made_50yd_bonus)
+/// Tiered points for the opponent's score. Players have no `points_allowed` and get zero.
+fn points_allowed_col(scoring: Scoring) -> Expr {
+    let pa = col("points_allowed");
+    when(pa.clone().lt_eq(lit(0.0)))
+        .then(lit(scoring.points_allowed_0_points))
+        .when(pa.clone().lt_eq(lit(6.0)))
+        .then(lit(scoring.points_allowed_1_6_points))
+        .when(pa.clone().lt_eq(lit(13.0)))
+        .then(lit(scoring.points_allowed_7_13_points))
+        .when(pa.clone().lt_eq(lit(20.0)))
+        .then(lit(scoring.points_allowed_14_20_points))
+        .when(pa.clone().lt_eq(lit(27.0)))
+        .then(lit(scoring.points_allowed_21_27_points))
+        .when(pa.clone().lt_eq(lit(34.0)))
+        .then(lit(scoring.points_allowed_28_34_points))
+        .when(pa.is_not_null())
+        .then(lit(scoring.points_allowed_35plus_points))
+        .otherwise(lit(0.0))
+}
+
+/// Tiered points for the offensive yards given up. Players have no `yards_allowed` and get zero.
+fn yards_allowed_col(scoring: Scoring) -> Expr {
+    let ya = col("yards_allowed");
+    when(ya.clone().lt(lit(100.0)))
+        .then(lit(scoring.yards_allowed_0_99_points))
+        .when(ya.clone().lt(lit(200.0)))
+        .then(lit(scoring.yards_allowed_100_199_points))
+        .when(ya.clone().lt(lit(300.0)))
+        .then(lit(scoring.yards_allowed_200_299_points))
+        .when(ya.clone().lt(lit(350.0)))
+        .then(lit(scoring.yards_allowed_300_349_points))
+        .when(ya.clone().lt(lit(400.0)))
+        .then(lit(scoring.yards_allowed_350_399_points))
+        .when(ya.clone().lt(lit(450.0)))
+        .then(lit(scoring.yards_allowed_400_449_points))
+        .when(ya.clone().lt(lit(500.0)))
+        .then(lit(scoring.yards_allowed_450_499_points))
+        .when(ya.is_not_null())
+        .then(lit(scoring.yards_allowed_500plus_points))
+        .otherwise(lit(0.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pbp;
+    use serde_json::json;
+
+    fn stats(plays: &[Value]) -> DataFrame {
+        pbp::tests::plays(plays)
+            .fantasy_stats()
+            .unwrap()
+            .collect()
+            .unwrap()
+    }
+
+    /// Total of a stat over a player's rows, with missing stats as 0
+    fn stat(df: &DataFrame, player_id: &str, name: &str) -> f64 {
+        df.clone()
+            .lazy()
+            .filter(col("player_id").eq(lit(player_id)))
+            .select([col(name).cast(DataType::Float64).sum()])
+            .collect()
+            .unwrap()
+            .column(name)
+            .unwrap()
+            .f64()
+            .unwrap()
+            .get(0)
+            .unwrap_or(0.0)
+    }
+
+    /// BUF wins 21-15 at KC without its offense scoring: a pick-six and PAT, a punt return TD, a
+    /// blocked field goal return TD, and a safety. KC scores a kickoff return TD and PAT, and a
+    /// fumble recovery TD in the end zone and two-point conversion.
+    fn defensive_game() -> Vec<Value> {
+        let score = |play: Value| {
+            let mut play = play;
+            play["home_score"] = json!(15.0);
+            play["away_score"] = json!(21.0);
+            play
+        };
+        [
+            json!({
+                "play_type": "pass", "passer_player_id": "QB1", "passer_player_name": "QB1",
+                "interception": 1.0, "interception_player_id": "DB2",
+                "interception_player_name": "DB2", "touchdown": 1.0, "return_touchdown": 1.0,
+                "td_team": "BUF", "td_player_id": "DB2", "td_player_name": "DB2",
+            }),
+            json!({
+                "posteam": "BUF", "play_type": "extra_point", "extra_point_attempt": 1.0,
+                "extra_point_result": "good", "kicker_player_id": "K2", "kicker_player_name": "K2",
+            }),
+            json!({
+                "play_type": "kickoff", "kickoff_returner_player_id": "KR1",
+                "kickoff_returner_player_name": "KR1", "touchdown": 1.0, "return_touchdown": 1.0,
+                "td_team": "KC", "td_player_id": "KR1", "td_player_name": "KR1",
+            }),
+            json!({
+                "play_type": "extra_point", "extra_point_attempt": 1.0,
+                "extra_point_result": "good", "kicker_player_id": "K1", "kicker_player_name": "K1",
+            }),
+            json!({
+                "play_type": "punt", "punt_returner_player_id": "PR2",
+                "punt_returner_player_name": "PR2", "touchdown": 1.0, "return_touchdown": 1.0,
+                "td_team": "BUF", "td_player_id": "PR2", "td_player_name": "PR2",
+            }),
+            json!({
+                "play_type": "field_goal", "field_goal_result": "blocked", "kick_distance": 40.0,
+                "kicker_player_id": "K1", "kicker_player_name": "K1", "touchdown": 1.0,
+                "return_touchdown": 1.0, "td_team": "BUF", "td_player_id": "DB2",
+                "td_player_name": "DB2",
+            }),
+            json!({
+                "play_type": "run", "rusher_player_id": "RB1", "rusher_player_name": "RB1",
+                "rushing_yards": -2.0, "yards_gained": -2, "safety": 1.0,
+            }),
+            json!({
+                "play_type": "run", "rusher_player_id": "RB1", "rusher_player_name": "RB1",
+                "rushing_yards": 4.0, "yards_gained": 5, "fumbled_1_player_id": "RB1",
+                "fumbled_1_player_name": "RB1", "fumble_recovery_1_player_id": "WR1",
+                "fumble_recovery_1_player_name": "WR1", "touchdown": 1.0, "rush_touchdown": 1.0,
+                "td_team": "KC", "td_player_id": "WR1", "td_player_name": "WR1",
+            }),
+            json!({
+                "play_type": "pass", "two_point_attempt": 1.0,
+                "two_point_conv_result": "success", "passer_player_id": "QB1",
+                "passer_player_name": "QB1", "receiver_player_id": "TE1",
+                "receiver_player_name": "TE1", "complete_pass": 1.0,
+            }),
+        ]
+        .into_iter()
+        .map(score)
+        .collect()
+    }
+
+    #[test]
+    fn credits_individual_defenders_with_their_team() {
+        let df = stats(&[
+            json!({
+                "play_type": "pass", "sack": 1.0, "sack_player_id": "LB2",
+                "sack_player_name": "LB2", "qb_hit_1_player_id": "LB2",
+                "qb_hit_1_player_name": "LB2", "tackle_for_loss_1_player_id": "LB2",
+                "tackle_for_loss_1_player_name": "LB2",
+            }),
+            json!({
+                "play_type": "pass", "sack": 1.0, "half_sack_1_player_id": "DL2",
+                "half_sack_1_player_name": "DL2", "half_sack_2_player_id": "LB2",
+                "half_sack_2_player_name": "LB2",
+            }),
+            json!({
+                "play_type": "run", "assist_tackle_1_player_id": "LB2",
+                "assist_tackle_1_player_name": "LB2", "assist_tackle_1_team": "BUF",
+                "assist_tackle_2_player_id": "DL2", "assist_tackle_2_player_name": "DL2",
+                "assist_tackle_2_team": "BUF", "forced_fumble_player_1_player_id": "DL2",
+                "forced_fumble_player_1_player_name": "DL2",
+                "forced_fumble_player_1_team": "BUF",
+            }),
+            // WR1 tackles DB2 after the interception
+            json!({
+                "play_type": "pass", "interception": 1.0, "interception_player_id": "DB2",
+                "interception_player_name": "DB2", "pass_defense_1_player_id": "DB2",
+                "pass_defense_1_player_name": "DB2", "solo_tackle_1_player_id": "WR1",
+                "solo_tackle_1_player_name": "WR1", "solo_tackle_1_team": "KC",
+            }),
+        ]);
+        assert_eq!(stat(&df, "LB2", "idp_sacks"), 1.0);
+        assert_eq!(stat(&df, "LB2", "idp_half_sacks"), 1.0);
+        assert_eq!(stat(&df, "LB2", "idp_qb_hits"), 1.0);
+        assert_eq!(stat(&df, "LB2", "idp_tfl"), 1.0);
+        assert_eq!(stat(&df, "LB2", "idp_assist_tackles"), 1.0);
+        assert_eq!(stat(&df, "DL2", "idp_forced_fumbles"), 1.0);
+        assert_eq!(stat(&df, "DB2", "idp_interceptions"), 1.0);
+        assert_eq!(stat(&df, "DB2", "idp_pass_defended"), 1.0);
+        assert_eq!(stat(&df, "WR1", "idp_solo_tackles"), 1.0);
+
+        let teams = df
+            .lazy()
+            .filter(col("player_id").is_in(lit(Series::new("ids", ["LB2", "WR1"]))))
+            .sort(["player_id"], SortMultipleOptions::default())
+            .collect()
+            .unwrap();
+        let teams: Vec<_> = teams
+            .column("team")
+            .unwrap()
+            .str()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+        assert_eq!(teams, ["BUF", "KC"]);
+    }
+
+    #[test]
+    fn scores_individual_defenders() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../leagues/idp.toml");
+        let scores = pbp::tests::plays(&[json!({
+            "play_type": "pass", "sack": 1.0, "sack_player_id": "LB2", "sack_player_name": "LB2",
+            "qb_hit_1_player_id": "LB2", "qb_hit_1_player_name": "LB2",
+            "forced_fumble_player_1_player_id": "LB2", "forced_fumble_player_1_player_name": "LB2",
+            "forced_fumble_player_1_team": "BUF",
+        })])
+        .fantasy_stats()
+        .unwrap()
+        .score(Scoring::from_file(path).unwrap())
+        .unwrap();
+        // A sack, a QB hit, and a forced fumble
+        assert_eq!(stat(&scores, "LB2", "fantasy_points"), 2.0 + 0.5 + 2.0);
+    }
+
+    #[test]
+    fn splits_two_point_conversions_between_passer_receiver_and_rusher() {
+        let conversion = |result: &str, play: Value| {
+            let mut play = play;
+            play["two_point_attempt"] = json!(1.0);
+            play["two_point_conv_result"] = json!(result);
+            play
+        };
+        let pass = json!({
+            "play_type": "pass", "passer_player_id": "QB1", "passer_player_name": "QB1",
+            "receiver_player_id": "TE1", "receiver_player_name": "TE1",
+        });
+        let run = json!({
+            "play_type": "run", "rusher_player_id": "RB1", "rusher_player_name": "RB1",
+        });
+        let df = stats(&[
+            conversion("success", pass.clone()),
+            conversion("failure", pass.clone()),
+            conversion("success", run.clone()),
+            conversion("failure", run),
+        ]);
+        assert_eq!(stat(&df, "QB1", "two_pt_pass"), 1.0);
+        assert_eq!(stat(&df, "TE1", "two_pt_rec"), 1.0);
+        assert_eq!(stat(&df, "RB1", "two_pt_rush"), 1.0);
+        for column in ["two_pt_rec", "two_pt_rush"] {
+            assert_eq!(stat(&df, "QB1", column), 0.0, "{}", column);
+        }
+        // Conversions aren't targets or carries
+        assert_eq!(stat(&df, "TE1", "targets"), 0.0);
+        assert_eq!(stat(&df, "RB1", "carries"), 0.0);
+
+        let mut scoring = Scoring::ppr();
+        scoring.two_pt_pass_points = 1.0;
+        let scores = pbp::tests::plays(&[conversion("success", pass)])
+            .fantasy_stats()
+            .unwrap()
+            .score(scoring)
+            .unwrap();
+        assert_eq!(stat(&scores, "QB1", "fantasy_points"), 1.0);
+        assert_eq!(stat(&scores, "TE1", "fantasy_points"), 2.0);
+    }
+
+    #[test]
+    fn itemizes_kicking_and_bonus_points() {
+        let kick = |play_type: &str, result: &str, distance: f64| {
+            let mut play = json!({
+                "play_type": play_type, "kicker_player_id": "K1", "kicker_player_name": "K1",
+                "kick_distance": distance,
+            });
+            match play_type {
+                "field_goal" => play["field_goal_result"] = json!(result),
+                _ => play["extra_point_result"] = json!(result),
+            }
+            play
+        };
+        let long_pass = json!({
+            "play_type": "pass", "passer_player_id": "QB1", "passer_player_name": "QB1",
+            "receiver_player_id": "WR1", "receiver_player_name": "WR1", "complete_pass": 1.0,
+            "passing_yards": 310.0, "receiving_yards": 310.0,
+        });
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../leagues/shawn.toml");
+        let scoring = Scoring::from_file(path).unwrap();
+        let df = pbp::tests::plays(&[
+            kick("field_goal", "made", 45.0),
+            kick("field_goal", "made", 52.0),
+            kick("field_goal", "missed", 30.0),
+            kick("extra_point", "good", 33.0),
+            kick("extra_point", "failed", 33.0),
+            long_pass,
+        ])
+        .fantasy_stats()
+        .unwrap()
+        .score_breakdown(scoring)
+        .unwrap();
+
+        // Two field goals and a PAT, with bonuses for both 40+ and one 50+ yard field goal
+        assert_eq!(stat(&df, "K1", "kicking_pts"), 7.0);
+        assert_eq!(stat(&df, "K1", "kicking_bonus_pts"), 3.0);
+        assert_eq!(stat(&df, "QB1", "passing_bonus_pts"), 1.0);
+        assert_eq!(stat(&df, "WR1", "receiving_bonus_pts"), 2.0);
+
+        // The components add up to the score
+        let components = df
+            .get_column_names()
+            .into_iter()
+            .filter(|name| name.ends_with("_pts"))
+            .map(col)
+            .reduce(|total, points| total + points)
+            .unwrap();
+        let totals = df
+            .clone()
+            .lazy()
+            .select([col("fantasy_points"), components.alias("total")])
+            .collect()
+            .unwrap();
+        assert_eq!(
+            totals.column("fantasy_points").unwrap(),
+            totals.column("total").unwrap()
+        );
+        assert_eq!(stat(&df, "K1", "fantasy_points"), 10.0);
+    }
+
+    #[test]
+    fn compares_points_against_each_opponent_to_the_player_average() {
+        let run = |week: i32, opponent: &str, yards: f64| {
+            json!({
+                "game_id": format!("2023_{:02}_{}_KC", week, opponent), "week": week,
+                "away_team": opponent, "defteam": opponent, "play_type": "run",
+                "rusher_player_id": "RB1", "rusher_player_name": "RB1", "rushing_yards": yards,
+            })
+        };
+        let fantasy_stats = pbp::tests::plays(&[
+            run(1, "BUF", 100.0),
+            run(2, "DEN", 300.0),
+            run(3, "BUF", 200.0),
+        ])
+        .fantasy_stats()
+        .unwrap();
+        let df = fantasy_stats
+            .clone()
+            .filter(col("player_id").eq(lit("RB1")))
+            .unwrap()
+            .collect()
+            .unwrap();
+        let mut opponents: Vec<_> = df
+            .column("opponent")
+            .unwrap()
+            .str()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+        opponents.sort();
+        assert_eq!(opponents, ["BUF", "BUF", "DEN"]);
+
+        let splits = fantasy_stats
+            .score_by_player_opponent(Scoring::ppr())
+            .unwrap();
+        let split = |opponent: &str, name: &str| {
+            let df = splits
+                .clone()
+                .lazy()
+                .filter(col("opponent").eq(lit(opponent)))
+                .collect()
+                .unwrap();
+            stat(&df, "RB1", name)
+        };
+        // 20 points per game on average
+        assert_eq!(split("BUF", "games"), 2.0);
+        assert_eq!(split("BUF", "points_per_game"), 15.0);
+        assert_eq!(split("BUF", "vs_avg"), -5.0);
+        assert_eq!(split("DEN", "points_per_game"), 30.0);
+        assert_eq!(split("DEN", "vs_avg"), 10.0);
+    }
+
+    #[test]
+    fn gives_long_touchdown_bonuses_only_to_the_scorer() {
+        let long_gain = |td_player_id: &str| {
+            json!({
+                "play_type": "pass", "passer_player_id": "QB1", "passer_player_name": "QB1",
+                "receiver_player_id": "WR1", "receiver_player_name": "WR1", "complete_pass": 1.0,
+                "passing_yards": 60.0, "receiving_yards": 60.0, "touchdown": 1.0,
+                "pass_touchdown": 1.0, "td_team": "KC", "td_player_id": td_player_id,
+                "td_player_name": td_player_id, "fumble_recovery_1_player_id": "TE1",
+                "fumble_recovery_1_player_name": "TE1",
+            })
+        };
+        let long_run = json!({
+            "play_type": "run", "rusher_player_id": "RB1", "rusher_player_name": "RB1",
+            "rushing_yards": 55.0, "touchdown": 1.0, "rush_touchdown": 1.0, "td_team": "KC",
+            "td_player_id": "RB1", "td_player_name": "RB1",
+        });
+        // WR1 scores once, and fumbles into the end zone for TE1 once
+        let df = stats(&[long_gain("WR1"), long_gain("TE1"), long_run]);
+        assert_eq!(stat(&df, "WR1", "receiving_touchdowns"), 1.0);
+        assert_eq!(stat(&df, "WR1", "receiving_50yd_td"), 1.0);
+        assert_eq!(stat(&df, "TE1", "fumble_recovery_tds"), 1.0);
+        assert_eq!(stat(&df, "RB1", "rushing_50yd_td"), 1.0);
+    }
+
+    #[test]
+    fn credits_defensive_and_special_teams_touchdowns() {
+        let df = stats(&defensive_game());
+        assert_eq!(stat(&df, "BUF", "def_interceptions"), 1.0);
+        assert_eq!(stat(&df, "BUF", "def_touchdowns"), 2.0);
+        assert_eq!(stat(&df, "BUF", "def_blocked_kicks"), 1.0);
+        assert_eq!(stat(&df, "BUF", "def_safeties"), 1.0);
+        assert_eq!(stat(&df, "BUF", "st_touchdowns"), 1.0);
+        assert_eq!(stat(&df, "KC", "def_touchdowns"), 0.0);
+        assert_eq!(stat(&df, "KC", "st_touchdowns"), 1.0);
+    }
+
+    #[test]
+    fn credits_kick_returners_but_not_defensive_returns() {
+        let df = stats(&defensive_game());
+        assert_eq!(stat(&df, "KR1", "td_returns"), 1.0);
+        assert_eq!(stat(&df, "PR2", "td_returns"), 1.0);
+        assert_eq!(stat(&df, "DB2", "td_returns"), 0.0);
+        assert_eq!(df.column("player_id").unwrap().null_count(), 0);
+    }
+
+    #[test]
+    fn leaves_scores_the_defense_was_not_on_the_field_for_out_of_points_allowed() {
+        let df = stats(&defensive_game());
+        // KC's 15, less the kickoff return TD and its PAT
+        assert_eq!(stat(&df, "BUF", "points_allowed"), 8.0);
+        // BUF's 21 came from the pick-six and PAT, punt and blocked kick returns, and safety
+        assert_eq!(stat(&df, "KC", "points_allowed"), 0.0);
+        let scores = pbp::tests::plays(&defensive_game())
+            .fantasy_stats()
+            .unwrap()
+            .score(Scoring::ppr())
+            .unwrap();
+        // An interception, 2 TDs, a block, a safety, and a return TD, plus 4 for allowing 7-13
+        assert_eq!(
+            stat(&scores, "BUF", "fantasy_points"),
+            2.0 + 12.0 + 2.0 + 2.0 + 6.0 + 4.0
+        );
+    }
 }
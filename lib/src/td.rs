@@ -0,0 +1,277 @@
+use crate::{opportunity, pbp::PbpDf, scoring::Scoring, Result};
+use polars::prelude::*;
+
+/// League-wide touchdown rates for targets and carries by field position, fit on the loaded
+/// seasons. Touchdowns are rare and swing fantasy scores, so players who score far more (or
+/// fewer) than their opportunities suggest tend to come back toward expectation.
+#[derive(Clone, Debug)]
+pub struct TdModel {
+    rates: DataFrame,
+}
+
+impl TdModel {
+    /// Fits touchdown rates on every target and carry in the plays, e.g. all loaded seasons
+    pub fn fit(pbp_df: PbpDf) -> Result<Self> {
+        let rates = opportunities(pbp_df.lazy())?
+            .group_by([col("play_type"), col("field_position")])
+            .agg([
+                len().alias("opportunities"),
+                col("touchdowns").mean().alias("td_rate"),
+            ])
+            .sort(
+                ["play_type", "field_position"],
+                SortMultipleOptions::default(),
+            )
+            .collect()?;
+        log::debug!("Fit touchdown rates for {} situations", rates.height());
+        Ok(Self { rates })
+    }
+
+    /// Touchdown rate for each play type (`target` or `carry`) and field position (the far edge
+    /// of each band of `yardline_100`, e.g. 5 for inside the 5)
+    pub fn rates(&self) -> &DataFrame {
+        &self.rates
+    }
+
+    /// Adds `expected_touchdowns` to plays with `play_type` and `field_position` columns
+    fn expected(&self, plays: LazyFrame) -> LazyFrame {
+        let rates = self.rates.clone().lazy().select([
+            col("play_type"),
+            col("field_position"),
+            col("td_rate"),
+        ]);
+        plays
+            .join(
+                rates,
+                [col("play_type"), col("field_position")],
+                [col("play_type"), col("field_position")],
+                JoinArgs::new(JoinType::Left),
+            )
+            .with_column(
+                col("td_rate")
+                    .fill_null(lit(0.0))
+                    .alias("expected_touchdowns"),
+            )
+    }
+
+    /// Targets, carries, and actual and expected receiving and rushing touchdowns per player-game
+    /// from the targets and carries in the plays
+    pub fn expected_touchdowns(&self, pbp_df: PbpDf) -> Result<DataFrame> {
+        let is = |play_type: &str| col("play_type").eq(lit(play_type));
+        let sum = |name: &str, play_type: &str| col(name).filter(is(play_type)).sum();
+        let df = self
+            .expected(opportunities(pbp_df.lazy())?)
+            .group_by([col("game_id"), col("player_id")])
+            .agg([
+                is("target").sum().alias("targets"),
+                is("carry").sum().alias("carries"),
+                sum("touchdowns", "target").alias("receiving_tds"),
+                sum("touchdowns", "carry").alias("rushing_tds"),
+                sum("expected_touchdowns", "target").alias("expected_receiving_tds"),
+                sum("expected_touchdowns", "carry").alias("expected_rushing_tds"),
+            ])
+            .collect()?;
+        Ok(df)
+    }
+}
+
+/// Scored player-games (from `FantasyStatsDf::score`) with their expected touchdowns (from
+/// `TdModel::expected_touchdowns`), how many more touchdowns they scored than expected, and the
+/// `expected_td_points` they'd have scored with expected touchdowns instead of actual ones.
+/// Targets, carries, and touchdowns are counted like the expected ones, so e.g. a scramble
+/// touchdown stays in `expected_td_points` as scored. Player-games without targets or carries are
+/// left out.
+pub fn td_regression(
+    scores: DataFrame,
+    expected: DataFrame,
+    scoring: Scoring,
+) -> Result<DataFrame> {
+    let stat = |name: &str| col(name).fill_null(lit(0.0));
+    let touchdowns = stat("receiving_tds") + stat("rushing_tds");
+    let expected_touchdowns = stat("expected_receiving_tds") + stat("expected_rushing_tds");
+    let td_points_over_expected = (stat("receiving_tds") - stat("expected_receiving_tds"))
+        * lit(scoring.receiving_td_points)
+        + (stat("rushing_tds") - stat("expected_rushing_tds")) * lit(scoring.rushing_td_points);
+
+    let df = scores
+        .lazy()
+        .select([
+            col("game_id"),
+            col("season"),
+            col("week"),
+            col("team"),
+            col("player_id"),
+            col("player_name"),
+            col("fantasy_points"),
+        ])
+        .join(
+            expected.lazy(),
+            [col("game_id"), col("player_id")],
+            [col("game_id"), col("player_id")],
+            JoinArgs::new(JoinType::Inner),
+        )
+        .select([
+            col("game_id"),
+            col("season"),
+            col("week"),
+            col("team"),
+            col("player_id"),
+            col("player_name"),
+            col("targets"),
+            col("carries"),
+            touchdowns.clone().alias("touchdowns"),
+            expected_touchdowns.clone().alias("expected_touchdowns"),
+            (touchdowns - expected_touchdowns).alias("td_over_expected"),
+            col("fantasy_points"),
+            (col("fantasy_points") - td_points_over_expected).alias("expected_td_points"),
+        ])
+        .sort(
+            ["td_over_expected"],
+            SortMultipleOptions::default().with_order_descending(true),
+        )
+        .collect()?;
+    Ok(df)
+}
+
+/// Totals of `td_regression` per player
+pub fn td_regression_by_player(player_games: DataFrame) -> Result<DataFrame> {
+    let df = player_games
+        .lazy()
+        .group_by([col("player_id")])
+        .agg([
+            cols(["player_name", "team"]).last(),
+            col("game_id").n_unique().alias("games"),
+            cols([
+                "targets",
+                "carries",
+                "touchdowns",
+                "expected_touchdowns",
+                "td_over_expected",
+                "fantasy_points",
+                "expected_td_points",
+            ])
+            .sum(),
+        ])
+        .sort(
+            ["td_over_expected"],
+            SortMultipleOptions::default().with_order_descending(true),
+        )
+        .collect()?;
+    Ok(df)
+}
+
+/// Every target and carry, with its `play_type`, `field_position`, and `touchdowns`
+fn opportunities(plays: LazyFrame) -> Result<LazyFrame> {
+    let columns = || {
+        [
+            col("game_id"),
+            col("player_id"),
+            col("play_type"),
+            col("field_position"),
+            col("touchdowns"),
+        ]
+    };
+    let targets = opportunity::targets(plays.clone())
+        .with_column(lit("target").alias("play_type"))
+        .select(columns());
+    let carries = opportunity::carries(plays)
+        .with_column(lit("carry").alias("play_type"))
+        .select(columns());
+    let lf = concat([targets, carries], UnionArgs::default())?;
+    Ok(lf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pbp;
+    use serde_json::{json, Value};
+
+    fn carry(player_id: &str, touchdown: bool) -> Value {
+        let mut play = json!({
+            "play_type": "run",
+            "yardline_100": 3,
+            "rusher_player_id": player_id,
+            "rusher_player_name": player_id,
+            "rushing_yards": 1.0,
+        });
+        if touchdown {
+            let td = json!({
+                "rushing_yards": 3.0,
+                "touchdown": 1.0,
+                "rush_touchdown": 1.0,
+                "td_team": "KC",
+                "td_player_id": player_id,
+                "td_player_name": player_id,
+            });
+            play.as_object_mut()
+                .unwrap()
+                .extend(td.as_object().unwrap().clone());
+        }
+        play
+    }
+
+    fn scramble(touchdown: bool) -> Value {
+        let mut play = carry("QB1", touchdown);
+        play["qb_scramble"] = json!(1.0);
+        play
+    }
+
+    /// The regression of QB1, who has one designed carry and one scramble
+    fn qb_regression(scramble_td: bool) -> DataFrame {
+        let pbp_df = pbp::tests::plays(&[
+            carry("RB1", true),
+            carry("RB1", false),
+            carry("RB1", false),
+            carry("QB1", false),
+            scramble(scramble_td),
+        ]);
+        let scoring = Scoring::ppr();
+        let model = TdModel::fit(pbp_df.clone()).unwrap();
+        let expected = model.expected_touchdowns(pbp_df.clone()).unwrap();
+        let scores = pbp_df.fantasy_stats().unwrap().score(scoring).unwrap();
+        td_regression(scores, expected, scoring)
+            .unwrap()
+            .lazy()
+            .filter(col("player_id").eq(lit("QB1")))
+            .collect()
+            .unwrap()
+    }
+
+    fn value(df: &DataFrame, name: &str) -> f64 {
+        df.column(name)
+            .unwrap()
+            .cast(&DataType::Float64)
+            .unwrap()
+            .f64()
+            .unwrap()
+            .get(0)
+            .unwrap()
+    }
+
+    #[test]
+    fn fits_rates_on_designed_carries() {
+        let pbp_df = pbp::tests::plays(&[carry("RB1", true), carry("RB1", false), scramble(true)]);
+        let model = TdModel::fit(pbp_df).unwrap();
+        let rates = model.rates();
+        assert_eq!(value(rates, "opportunities"), 2.0);
+        assert_eq!(value(rates, "td_rate"), 0.5);
+    }
+
+    #[test]
+    fn leaves_scramble_touchdowns_out_of_the_regression() {
+        let without = qb_regression(false);
+        let with = qb_regression(true);
+        assert_eq!(value(&with, "carries"), 1.0);
+        assert_eq!(value(&with, "touchdowns"), 0.0);
+        assert_eq!(value(&with, "expected_touchdowns"), 0.25);
+        assert_eq!(
+            value(&with, "td_over_expected"),
+            value(&without, "td_over_expected")
+        );
+        let td_points =
+            |df: &DataFrame| value(df, "fantasy_points") - value(df, "expected_td_points");
+        assert_eq!(td_points(&with), td_points(&without));
+        assert_eq!(td_points(&with), -1.5);
+    }
+}
@@ -0,0 +1,197 @@
+use crate::{opportunity, scoring::FantasyStatsDf, Result};
+use polars::prelude::*;
+
+/// Usage columns added to each player-game by `PbpDf::fantasy_stats`
+pub const USAGE_STATS: [&str; 9] = [
+    "targets",
+    "target_share",
+    "air_yards",
+    "air_yards_share",
+    "carries",
+    "rush_share",
+    "red_zone_opportunities",
+    "goal_to_go_opportunities",
+    "wopr",
+];
+
+/// Opportunities per player-game: targets and carries, the player's share of their team's, red
+/// zone (inside the 20) and goal-to-go opportunities, and weighted opportunity rating
+/// (`wopr = 1.5 * target_share + 0.7 * air_yards_share`), which tracks receiving production better
+/// than either share alone. Targets and carries are those of xFP, so carries are designed runs.
+pub(crate) fn usage_stats(plays: LazyFrame) -> Result<LazyFrame> {
+    let keys = [
+        col("game_id"),
+        col("season"),
+        col("week"),
+        col("team"),
+        col("player_id"),
+        col("player_name"),
+    ];
+    // Counts are unsigned, which don't mix with integer literals
+    let count = |condition: Expr| condition.sum().cast(DataType::Int64);
+    let red_zone = || col("field_position").lt_eq(lit(20));
+    let goal_to_go = || col("goal_to_go").eq(lit(1));
+    let targets_lf = opportunity::targets(plays.clone())
+        .group_by(keys.clone())
+        .agg([
+            len().cast(DataType::Int64).alias("targets"),
+            col("air_yards").fill_null(lit(0.0)).sum(),
+            count(red_zone()).alias("red_zone_targets"),
+            count(goal_to_go()).alias("goal_to_go_targets"),
+        ]);
+    let carries_lf = opportunity::carries(plays).group_by(keys.clone()).agg([
+        len().cast(DataType::Int64).alias("carries"),
+        count(red_zone()).alias("red_zone_carries"),
+        count(goal_to_go()).alias("goal_to_go_carries"),
+    ]);
+
+    let join_args = JoinArgs::new(JoinType::Full).with_coalesce(JoinCoalesce::CoalesceColumns);
+    let team_game = [col("game_id"), col("team")];
+    // Every target and carry is some player's, so the team's totals are the sum over its players
+    let share = |name: &str| {
+        let stat = col(name).cast(DataType::Float64).fill_null(lit(0.0));
+        let team_total = stat.clone().sum().over(team_game.clone());
+        when(team_total.clone().gt(lit(0.0)))
+            .then(stat / team_total)
+            .otherwise(lit(0.0))
+    };
+    let opportunities = |targets: &str, carries: &str| {
+        col(targets).fill_null(lit(0)) + col(carries).fill_null(lit(0))
+    };
+
+    let lf = targets_lf
+        .join(carries_lf, keys.clone(), keys, join_args)
+        .with_columns([
+            col("targets").fill_null(lit(0)),
+            col("air_yards").fill_null(lit(0.0)),
+            col("carries").fill_null(lit(0)),
+            share("targets").alias("target_share"),
+            share("air_yards").alias("air_yards_share"),
+            share("carries").alias("rush_share"),
+            opportunities("red_zone_targets", "red_zone_carries").alias("red_zone_opportunities"),
+            opportunities("goal_to_go_targets", "goal_to_go_carries")
+                .alias("goal_to_go_opportunities"),
+        ])
+        .with_column(
+            (lit(1.5) * col("target_share") + lit(0.7) * col("air_yards_share")).alias("wopr"),
+        )
+        .select([
+            col("game_id"),
+            col("season"),
+            col("week"),
+            col("team"),
+            col("player_id"),
+            col("player_name"),
+            cols(USAGE_STATS),
+        ]);
+    Ok(lf)
+}
+
+/// Each player's usage over all their games: totals for counts, and per-game averages for shares
+/// and `wopr`. Sorted by `wopr`, so the most involved receivers come first.
+pub fn usage_by_player(fantasy_stats: FantasyStatsDf) -> Result<DataFrame> {
+    let df = fantasy_stats
+        .lazy()
+        .filter(col("player_id").is_not_null())
+        .group_by([col("player_id")])
+        .agg([
+            cols(["player_name", "team"]).last(),
+            col("game_id").n_unique().alias("games"),
+            cols([
+                "targets",
+                "air_yards",
+                "carries",
+                "red_zone_opportunities",
+                "goal_to_go_opportunities",
+            ])
+            .sum(),
+            cols(["target_share", "air_yards_share", "rush_share", "wopr"])
+                .fill_null(lit(0.0))
+                .mean(),
+        ])
+        .sort(
+            ["wopr", "rush_share"],
+            SortMultipleOptions::default()
+                .with_order_descending(true)
+                .with_nulls_last(true),
+        )
+        .collect()?;
+    Ok(df)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pbp;
+    use serde_json::{json, Value};
+
+    fn run(player_id: &str, yardline_100: i32) -> Value {
+        json!({
+            "play_type": "run", "yardline_100": yardline_100, "rusher_player_id": player_id,
+            "rusher_player_name": player_id,
+        })
+    }
+
+    fn target(player_id: &str, air_yards: f64) -> Value {
+        json!({
+            "play_type": "pass", "yardline_100": 50, "passer_player_id": "QB1",
+            "passer_player_name": "QB1", "receiver_player_id": player_id,
+            "receiver_player_name": player_id, "air_yards": air_yards,
+        })
+    }
+
+    fn usage(plays: &[Value]) -> DataFrame {
+        usage_stats(pbp::tests::plays(plays).lazy())
+            .unwrap()
+            .sort(["player_id"], SortMultipleOptions::default())
+            .collect()
+            .unwrap()
+    }
+
+    fn values(df: &DataFrame, name: &str) -> Vec<f64> {
+        let column = df.column(name).unwrap().cast(&DataType::Float64).unwrap();
+        column.f64().unwrap().into_no_null_iter().collect()
+    }
+
+    #[test]
+    fn counts_designed_runs_as_carries() {
+        let mut kneel = run("QB1", 60);
+        kneel["qb_kneel"] = json!(1.0);
+        let mut scramble = run("QB1", 15);
+        scramble["qb_scramble"] = json!(1.0);
+        let mut two_point = run("RB1", 2);
+        two_point["two_point_attempt"] = json!(1.0);
+        let mut goal_line = run("RB1", 1);
+        goal_line["goal_to_go"] = json!(1.0);
+
+        let df = usage(&[
+            run("QB1", 30),
+            kneel,
+            scramble,
+            run("RB1", 15),
+            goal_line,
+            two_point,
+        ]);
+        assert_eq!(df.height(), 2);
+        assert_eq!(values(&df, "carries"), [1.0, 2.0]);
+        assert_eq!(values(&df, "rush_share"), [1.0 / 3.0, 2.0 / 3.0]);
+        assert_eq!(values(&df, "red_zone_opportunities"), [0.0, 2.0]);
+        assert_eq!(values(&df, "goal_to_go_opportunities"), [0.0, 1.0]);
+    }
+
+    #[test]
+    fn weights_target_and_air_yards_shares() {
+        let df = usage(&[
+            target("TE1", 10.0),
+            target("WR1", 20.0),
+            target("WR1", 10.0),
+            run("RB1", 30),
+        ]);
+        assert_eq!(values(&df, "targets"), [0.0, 1.0, 2.0]);
+        assert_eq!(values(&df, "air_yards_share"), [0.0, 0.25, 0.75]);
+        assert_eq!(
+            values(&df, "wopr"),
+            [0.0, 1.5 / 3.0 + 0.7 * 0.25, 1.0 + 0.7 * 0.75]
+        );
+    }
+}
@@ -0,0 +1,209 @@
+use crate::{league::League, roster::RosterDf, Result};
+use itertools::Itertools;
+use polars::prelude::*;
+use std::collections::HashMap;
+
+/// Value over replacement: how many more points each player scored than the best player at their
+/// position who wouldn't start in the league.
+///
+/// `scores` are one season's points per player, e.g. `FantasyStatsDf::score_by_player` over a
+/// single season. Positions come from the roster, and team defenses (whose player_id is their
+/// team) are `DST`. Flex slots (FLEX, IDP flex, then SUPERFLEX) go to whichever eligible player
+/// scored the most after the dedicated slots are filled, which raises the replacement level of the
+/// positions they're taken from.
+pub fn vor(scores: DataFrame, roster_df: RosterDf, league: &League) -> Result<DataFrame> {
+    let slot_positions = Series::new("slot_positions", league.slots.positions());
+    let players = roster_df
+        .add_positions(scores.lazy())?
+        .filter(col("position").is_in(lit(slot_positions)))
+        .sort(
+            ["fantasy_points"],
+            SortMultipleOptions::default().with_order_descending(true),
+        )
+        .collect()?;
+
+    let mut points: HashMap<&str, Vec<f64>> = HashMap::new();
+    let position_col = players.column("position")?.str()?;
+    let points_col = players.column("fantasy_points")?.f64()?;
+    for (position, player_points) in position_col.into_iter().zip(points_col) {
+        if let (Some(position), Some(player_points)) = (position, player_points) {
+            points.entry(position).or_default().push(player_points);
+        }
+    }
+
+    let mut starters: HashMap<&str, usize> = league
+        .slots
+        .positions()
+        .into_iter()
+        .map(|position| (position, 0))
+        .collect();
+    for (slot, _) in league.slots.starters() {
+        let count = league.starters(slot);
+        if !slot.is_flex() {
+            for position in slot.positions() {
+                *starters.get_mut(position).unwrap() += count as usize;
+            }
+            continue;
+        }
+
+        // Hand out flex slots one at a time to the best remaining eligible player
+        for _ in 0..count {
+            let next = slot
+                .positions()
+                .iter()
+                .filter_map(|position| {
+                    let next = points.get(position)?.get(starters[position])?;
+                    Some((*position, *next))
+                })
+                .max_by(|(_, a), (_, b)| a.total_cmp(b));
+            let Some((position, _)) = next else {
+                break;
+            };
+            *starters.get_mut(position).unwrap() += 1;
+        }
+    }
+
+    let (positions, starter_counts, replacement_points): (Vec<_>, Vec<_>, Vec<_>) = starters
+        .iter()
+        .sorted()
+        .map(|(position, &count)| {
+            // Without enough players to fill the league, anyone left is free
+            let replacement = points
+                .get(position)
+                .and_then(|points| points.get(count))
+                .copied()
+                .unwrap_or(0.0);
+            (*position, count as u32, replacement)
+        })
+        .multiunzip();
+    let replacement = df!(
+        "position" => positions,
+        "starters" => starter_counts,
+        "replacement_points" => replacement_points,
+    )?;
+
+    let rank = |name: &str| {
+        col(name).rank(
+            RankOptions {
+                method: RankMethod::Ordinal,
+                descending: true,
+            },
+            None,
+        )
+    };
+    let df = players
+        .lazy()
+        .join(
+            replacement.lazy(),
+            [col("position")],
+            [col("position")],
+            JoinArgs::new(JoinType::Left),
+        )
+        .with_column((col("fantasy_points") - col("replacement_points")).alias("vor"))
+        .with_columns([
+            rank("vor").alias("overall_rank"),
+            rank("fantasy_points")
+                .over([col("position")])
+                .alias("position_rank"),
+        ])
+        .select([
+            col("overall_rank"),
+            col("player_id"),
+            col("player_name"),
+            col("team"),
+            col("position"),
+            col("position_rank"),
+            col("fantasy_points"),
+            col("replacement_points"),
+            col("vor"),
+        ])
+        .sort(["overall_rank"], SortMultipleOptions::default())
+        .collect()?;
+    Ok(df)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn value(df: &DataFrame, player_id: &str, name: &str) -> f64 {
+        let df = df
+            .clone()
+            .lazy()
+            .filter(col("player_id").eq(lit(player_id)))
+            .collect()
+            .unwrap();
+        assert_eq!(df.height(), 1, "{player_id}");
+        df.column(name)
+            .unwrap()
+            .cast(&DataType::Float64)
+            .unwrap()
+            .f64()
+            .unwrap()
+            .get(0)
+            .unwrap()
+    }
+
+    #[test]
+    fn sets_replacement_levels_after_filling_flex_slots() {
+        let players = [
+            ("QB1", "QB", 300.0),
+            ("QB2", "QB", 250.0),
+            ("QB3", "QB", 200.0),
+            ("RB1", "RB", 200.0),
+            ("RB2", "RB", 150.0),
+            ("RB3", "RB", 120.0),
+            ("RB4", "RB", 80.0),
+            ("WR1", "WR", 180.0),
+            ("WR2", "WR", 140.0),
+            ("WR3", "WR", 130.0),
+            ("WR4", "WR", 100.0),
+            ("TE1", "TE", 90.0),
+            ("K1", "K", 150.0),
+        ];
+        let ids: Vec<_> = players.iter().map(|(id, _, _)| *id).collect();
+        let scores = df!(
+            "player_id" => [&ids[..], &["KC"]].concat(),
+            "player_name" => [&ids[..], &["KC"]].concat(),
+            "team" => vec!["KC"; ids.len() + 1],
+            "fantasy_points" => players
+                .iter()
+                .map(|(_, _, points)| *points)
+                .chain([100.0])
+                .collect::<Vec<_>>(),
+        )
+        .unwrap();
+        let roster_df = RosterDf::new(
+            df!(
+                "gsis_id" => &ids,
+                "position" => players.iter().map(|(_, position, _)| *position).collect::<Vec<_>>(),
+                "team" => vec!["KC"; ids.len()],
+            )
+            .unwrap()
+            .lazy(),
+        );
+        let league = League::from_value(json!({
+            "teams": 2,
+            "slots": { "qb": 1, "rb": 1, "wr": 1, "flex": 1, "dst": 1 },
+        }))
+        .unwrap();
+        let df = vor(scores, roster_df, &league).unwrap();
+
+        // Kickers don't start in this league
+        let ids = df.column("player_id").unwrap().str().unwrap();
+        assert!(!ids.into_no_null_iter().any(|id| id == "K1"));
+        assert_eq!(value(&df, "QB1", "replacement_points"), 200.0);
+        // The flex slots go to WR3 and then RB3, ahead of TE1
+        assert_eq!(value(&df, "RB1", "replacement_points"), 80.0);
+        assert_eq!(value(&df, "WR1", "replacement_points"), 100.0);
+        assert_eq!(value(&df, "TE1", "replacement_points"), 90.0);
+        assert_eq!(value(&df, "TE1", "vor"), 0.0);
+        // With only one defense for two teams, the replacement defense scores nothing
+        assert_eq!(value(&df, "KC", "replacement_points"), 0.0);
+        assert_eq!(value(&df, "KC", "vor"), 100.0);
+
+        assert_eq!(value(&df, "RB1", "overall_rank"), 1.0);
+        assert_eq!(value(&df, "QB2", "position_rank"), 2.0);
+    }
+}
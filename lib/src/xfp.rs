@@ -0,0 +1,230 @@
+use crate::{opportunity, pbp::PbpDf, scoring::Scoring, Result};
+use polars::prelude::*;
+
+/// Columns identifying a player-game, shared by targets and carries
+const KEYS: [&str; 7] = [
+    "game_id",
+    "season",
+    "week",
+    "team",
+    "opponent",
+    "player_id",
+    "player_name",
+];
+
+/// Expected fantasy points (xFP) for targets and carries, fit on league-wide plays.
+///
+/// Each target's and carry's value is what the average player got from the same situation:
+///
+/// - Targets are grouped by field position, depth (`air_yards`), and `pass_location`. Expected
+///   receptions and yards use the play's completion probability (`cp`) and expected yards after
+///   the catch (`xyac_mean_yardage`) when present, and the group's averages otherwise.
+/// - Carries are designed runs (not kneels or scrambles), grouped by field position and `run_gap`.
+/// - Expected touchdowns are the group's touchdown rate.
+///
+/// Points that differ a lot from expected tend to regress, so over-performers are sell candidates
+/// and under-performers buy candidates.
+#[derive(Clone, Debug)]
+pub struct XfpModel {
+    targets: DataFrame,
+    carries: DataFrame,
+}
+
+impl XfpModel {
+    /// Fits the model on every target and carry in the plays, e.g. all loaded seasons
+    pub fn fit(pbp_df: PbpDf) -> Result<Self> {
+        let plays = pbp_df.lazy();
+        let targets = opportunity::targets(plays.clone())
+            .group_by(target_groups())
+            .agg([
+                col("receptions").mean().alias("catch_rate"),
+                col("receiving_yards").mean().alias("yards_per_target"),
+                col("touchdowns").mean().alias("target_td_rate"),
+            ])
+            .collect()?;
+        let carries = opportunity::carries(plays)
+            .group_by(carry_groups())
+            .agg([
+                col("rushing_yards").mean().alias("yards_per_carry"),
+                col("touchdowns").mean().alias("carry_td_rate"),
+            ])
+            .collect()?;
+        log::debug!(
+            "Fit xFP on {} target and {} carry situations",
+            targets.height(),
+            carries.height()
+        );
+        Ok(Self { targets, carries })
+    }
+
+    /// Expected and actual points from each player-game's targets and carries in the plays.
+    /// `fantasy_points` only counts receiving and rushing, so it compares directly to `xfp`.
+    pub fn expected_points(&self, pbp_df: PbpDf, scoring: Scoring) -> Result<DataFrame> {
+        let plays = pbp_df.lazy();
+        let join_args = || JoinArgs::new(JoinType::Left);
+
+        let has_cp = col("cp").is_not_null();
+        let expected_receptions = when(has_cp.clone())
+            .then(col("cp"))
+            .otherwise(col("catch_rate"));
+        let expected_yards = when(
+            has_cp
+                .and(col("air_yards").is_not_null())
+                .and(col("xyac_mean_yardage").is_not_null()),
+        )
+        .then(col("cp") * (col("air_yards") + col("xyac_mean_yardage")))
+        .otherwise(col("yards_per_target"));
+        let receiving_points = |receptions: Expr, yards: Expr, touchdowns: Expr| {
+            receptions * lit(scoring.reception_points)
+                + yards / lit(scoring.receiving_yd_per_point)
+                + touchdowns * lit(scoring.receiving_td_points)
+        };
+        let targets = opportunity::targets(plays.clone())
+            .join(
+                self.targets.clone().lazy(),
+                target_groups(),
+                target_groups(),
+                join_args(),
+            )
+            .select([
+                cols(KEYS),
+                lit(1u32).alias("targets"),
+                lit(0u32).alias("carries"),
+                col("touchdowns"),
+                col("target_td_rate").alias("expected_touchdowns"),
+                receiving_points(col("receptions"), col("receiving_yards"), col("touchdowns"))
+                    .alias("fantasy_points"),
+                receiving_points(expected_receptions, expected_yards, col("target_td_rate"))
+                    .alias("xfp"),
+            ]);
+
+        let rushing_points = |yards: Expr, touchdowns: Expr| {
+            yards / lit(scoring.rushing_yd_per_point) + touchdowns * lit(scoring.rushing_td_points)
+        };
+        let carries = opportunity::carries(plays)
+            .join(
+                self.carries.clone().lazy(),
+                carry_groups(),
+                carry_groups(),
+                join_args(),
+            )
+            .select([
+                cols(KEYS),
+                lit(0u32).alias("targets"),
+                lit(1u32).alias("carries"),
+                col("touchdowns"),
+                col("carry_td_rate").alias("expected_touchdowns"),
+                rushing_points(col("rushing_yards"), col("touchdowns")).alias("fantasy_points"),
+                rushing_points(col("yards_per_carry"), col("carry_td_rate")).alias("xfp"),
+            ]);
+
+        let df = concat([targets, carries], UnionArgs::default())?
+            .group_by(KEYS.map(col))
+            .agg([cols([
+                "targets",
+                "carries",
+                "touchdowns",
+                "expected_touchdowns",
+                "fantasy_points",
+                "xfp",
+            ])
+            .sum()])
+            .with_column((col("fantasy_points") - col("xfp")).alias("points_over_expected"))
+            .sort(
+                ["points_over_expected"],
+                SortMultipleOptions::default().with_order_descending(true),
+            )
+            .collect()?;
+        Ok(df)
+    }
+}
+
+/// Totals of `XfpModel::expected_points` per player, with xFP per game
+pub fn xfp_by_player(player_games: DataFrame) -> Result<DataFrame> {
+    let df = player_games
+        .lazy()
+        .group_by([col("player_id")])
+        .agg([
+            cols(["player_name", "team"]).last(),
+            col("game_id").n_unique().alias("games"),
+            cols([
+                "targets",
+                "carries",
+                "touchdowns",
+                "expected_touchdowns",
+                "fantasy_points",
+                "xfp",
+                "points_over_expected",
+            ])
+            .sum(),
+        ])
+        .with_column((col("xfp") / col("games").cast(DataType::Float64)).alias("xfp_per_game"))
+        .sort(
+            ["points_over_expected"],
+            SortMultipleOptions::default().with_order_descending(true),
+        )
+        .collect()?;
+    Ok(df)
+}
+
+fn target_groups() -> [Expr; 3] {
+    [col("field_position"), col("depth"), col("pass_location")]
+}
+
+fn carry_groups() -> [Expr; 2] {
+    [col("field_position"), col("run_gap")]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pbp;
+    use serde_json::{json, Value};
+
+    fn run(player_id: &str, yards: f64, flag: Option<&str>) -> Value {
+        let mut play = json!({
+            "play_type": "run", "rusher_player_id": player_id, "rusher_player_name": player_id,
+            "rushing_yards": yards,
+        });
+        if let Some(flag) = flag {
+            play[flag] = json!(1.0);
+        }
+        play
+    }
+
+    fn value(df: &DataFrame, player_id: &str, name: &str) -> f64 {
+        let df = df
+            .clone()
+            .lazy()
+            .filter(col("player_id").eq(lit(player_id)))
+            .collect()
+            .unwrap();
+        assert_eq!(df.height(), 1, "{player_id}");
+        df.column(name)
+            .unwrap()
+            .cast(&DataType::Float64)
+            .unwrap()
+            .f64()
+            .unwrap()
+            .get(0)
+            .unwrap()
+    }
+
+    #[test]
+    fn leaves_kneels_and_scrambles_out_of_carries() {
+        let pbp_df = pbp::tests::plays(&[
+            run("RB1", 10.0, None),
+            run("QB1", 2.0, None),
+            run("QB1", -1.0, Some("qb_kneel")),
+            run("QB1", 30.0, Some("qb_scramble")),
+        ]);
+        let model = XfpModel::fit(pbp_df.clone()).unwrap();
+        let df = model.expected_points(pbp_df, Scoring::ppr()).unwrap();
+
+        // Both designed runs were up the middle, for 6 yards a carry
+        assert_eq!(value(&df, "QB1", "carries"), 1.0);
+        assert!((value(&df, "QB1", "fantasy_points") - 0.2).abs() < 1e-9);
+        assert!((value(&df, "QB1", "xfp") - 0.6).abs() < 1e-9);
+        assert!((value(&df, "RB1", "xfp") - 0.6).abs() < 1e-9);
+    }
+}